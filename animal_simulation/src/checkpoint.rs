@@ -0,0 +1,156 @@
+use crate::config::Config;
+use crate::{Evaluation, Species};
+use genetic_algorithm::{Genealogy, OptimizerState, Pedigree, PopulationState};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::{fmt, io};
+
+/// Bumped whenever the layout below changes; older checkpoints are rejected
+/// rather than misread.
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    Version(u32),
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Io(err) => write!(f, "checkpoint i/o failed: {err}"),
+            CheckpointError::Format(err) => write!(f, "malformed checkpoint: {err}"),
+            CheckpointError::Version(version) => write!(
+                f,
+                "checkpoint version {version} is not supported (expected {CHECKPOINT_VERSION})"
+            ),
+            CheckpointError::Unsupported(what) => write!(f, "cannot checkpoint {what}"),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CheckpointError::Io(err) => Some(err),
+            CheckpointError::Format(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CheckpointError {
+    fn from(err: io::Error) -> Self {
+        CheckpointError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for CheckpointError {
+    fn from(err: serde_json::Error) -> Self {
+        CheckpointError::Format(err)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Checkpoint {
+    pub(crate) version: u32,
+    pub(crate) config: Config,
+    pub(crate) generation: usize,
+    pub(crate) age: usize,
+    pub(crate) episode: usize,
+    pub(crate) rng: ChaCha8Rng,
+    pub(crate) population: Vec<BrainState>,
+    /// Whatever else `ga_population` keeps, e.g. ages or NSGA-II parents.
+    pub(crate) population_state: PopulationState<BrainState>,
+    pub(crate) predators: Vec<BrainState>,
+    pub(crate) worlds: Vec<WorldState>,
+    pub(crate) novelty: Vec<Vec<f32>>,
+    pub(crate) elites: Vec<EliteState>,
+    pub(crate) genealogy: Option<Genealogy>,
+    pub(crate) one_fifth_step: f32,
+    /// Set unless `ga_optimizer` is `Genetic`.
+    pub(crate) optimizer: Option<OptimizerState>,
+    /// Penalty weights of the prey and the predators.
+    pub(crate) constraint_weights: [f32; 2],
+    pub(crate) champion: Option<ChampionState>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct BrainState {
+    pub(crate) parameters: Vec<f32>,
+    pub(crate) evaluation: Evaluation,
+    pub(crate) mutation_step: f32,
+    pub(crate) parent_fitness: Option<f32>,
+    pub(crate) pedigree: Pedigree,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ChampionState {
+    pub(crate) brain: BrainState,
+    pub(crate) fitness: f32,
+    pub(crate) generation: usize,
+}
+
+/// A champion saved on its own, along with the config it evolved under.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ChampionFile {
+    pub(crate) version: u32,
+    pub(crate) config: Config,
+    pub(crate) champion: ChampionState,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct EliteState {
+    pub(crate) brain: BrainState,
+    pub(crate) fitness: f32,
+    pub(crate) behaviour: Vec<f32>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct WorldState {
+    pub(crate) rng: ChaCha8Rng,
+    pub(crate) foods: Vec<[f32; 2]>,
+    pub(crate) animals: Vec<AnimalState>,
+    pub(crate) deaths: Vec<BrainState>,
+}
+
+/// An animal and the index of its brain in the population.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct AnimalState {
+    /// Index into the population followed by the predators.
+    pub(crate) brain: usize,
+    pub(crate) species: Species,
+    pub(crate) position: [f32; 2],
+    pub(crate) rotation: f32,
+    pub(crate) vision: Vec<f32>,
+    pub(crate) speed: f32,
+    pub(crate) satiation: usize,
+    pub(crate) boosts: usize,
+    pub(crate) boosting: bool,
+    pub(crate) stunned: u8,
+    pub(crate) stunned_cooldown: u8,
+    pub(crate) stuns: usize,
+    pub(crate) attacks: usize,
+    pub(crate) hunger: usize,
+    pub(crate) ticks: usize,
+    pub(crate) speed_sum: f32,
+    pub(crate) position_cos_sum: [f32; 2],
+    pub(crate) position_sin_sum: [f32; 2],
+}
+
+/// Writes `value` as JSON next to `path` first, then moves it into place.
+pub(crate) fn write_atomically(path: &Path, value: &impl Serialize) -> Result<(), CheckpointError> {
+    let temporary = path.with_extension("tmp");
+    let mut writer = BufWriter::new(File::create(&temporary)?);
+    serde_json::to_writer(&mut writer, value)?;
+    writer.flush()?;
+    drop(writer);
+    fs::rename(temporary, path)?;
+
+    Ok(())
+}
@@ -1,7 +1,103 @@
+mod loading;
+mod validation;
+
+pub use loading::*;
+
 use crate::*;
-use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
-use std::cell::Cell;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptimizerKind {
+    #[default]
+    Genetic,
+    CmaEs,
+    OnePlusLambda,
+    MuCommaLambda,
+    NaturalEvolutionStrategy,
+    DifferentialEvolution,
+}
+
+/// A rule on an animal's behaviour, violated by how far it is overstepped.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimalConstraint {
+    /// Boosting in at most this fraction of ticks.
+    MaxBoostRatio(f32),
+    /// Stunned in at most this fraction of ticks.
+    MaxStunRatio(f32),
+}
+
+impl AnimalConstraint {
+    pub fn violation(&self, animal: &Animal) -> f32 {
+        match *self {
+            AnimalConstraint::MaxBoostRatio(max) => (animal.boost_ratio() - max).max(0.0),
+            AnimalConstraint::MaxStunRatio(max) => (animal.stun_ratio() - max).max(0.0),
+        }
+    }
+}
+
+/// How the brains are selected and replaced every generation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PopulationKind {
+    #[default]
+    RouletteWheel,
+    SteadyState {
+        replacements: usize,
+        policy: ReplacementPolicy,
+    },
+    Nsga2,
+    Alps {
+        layers: usize,
+        age_gap: usize,
+        scheme: AgingScheme,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MutationAdaptation {
+    #[default]
+    Fixed,
+    SelfAdaptive,
+    OneFifthRule,
+}
+
+/// Decay of `ga_mut_chance` and `ga_mut_coeff` by generation; `end` and
+/// `min` are fractions of the configured values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MutationDecay {
+    #[default]
+    None,
+    Linear {
+        end: f32,
+        generations: usize,
+    },
+    Exponential {
+        rate: f32,
+        min: f32,
+    },
+}
+
+impl MutationDecay {
+    pub(crate) fn schedule(&self, start: f32) -> Schedule {
+        match *self {
+            MutationDecay::None => Schedule::Constant(start),
+            MutationDecay::Linear { end, generations } => Schedule::Linear {
+                start,
+                end: start * end,
+                generations,
+            },
+            MutationDecay::Exponential { rate, min } => Schedule::Exponential {
+                start,
+                rate,
+                min: start * min,
+            },
+        }
+    }
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -16,19 +112,49 @@ pub struct Config {
     pub animal_size: f32,
     pub arc_size: f32,
 
-    pub ga_reverse: usize,
+    pub ga_population: PopulationKind,
+    pub ga_fitness_transforms: Vec<FitnessTransform>,
+    pub ga_constraints: Vec<AnimalConstraint>,
+    pub ga_constraint_handling: ConstraintHandling,
+    pub ga_fitness_aggregation: FitnessAggregation,
+    pub ga_reevaluate_elites: bool,
     pub ga_mut_chance: f32,
     pub ga_mut_coeff: f32,
+    pub ga_mut_adaptation: MutationAdaptation,
+    pub ga_mut_decay: MutationDecay,
+    pub ga_genealogy: bool,
+    /// Records beyond which the genealogy forgets every brain that is not
+    /// an ancestor of one alive.
+    pub ga_genealogy_limit: usize,
+    pub ga_novelty_weight: f32,
+    pub ga_novelty_k: usize,
+    pub ga_novelty_threshold: f32,
+    pub ga_novelty_archive: usize,
+    pub ga_map_elites_bins: usize,
+    pub ga_optimizer: OptimizerKind,
+    pub ga_optimizer_sigma: f32,
+    pub ga_optimizer_learning_rate: f32,
 
     pub sim_speed_min: f32,
     pub sim_speed_max: f32,
     pub sim_speed_accel: f32,
     pub sim_rotation_accel: f32,
     pub sim_generation_length: usize,
+    pub sim_worlds: usize,
+    pub sim_threads: usize,
+    pub sim_starvation_ticks: usize,
+    pub sim_predators: usize,
+    pub sim_episodes: usize,
 
     pub stun_duration: u8,
     pub stun_cooldown: u8,
     pub boost_cost: f32,
+    /// Fitness prey lose for every stun they take while `sim_predators` hunt
+    /// them, so that evading pays.
+    pub stun_penalty: f32,
+
+    pub stats_percentiles: Vec<f32>,
+    pub stats_histogram_bins: usize,
 
     pub world_animals: usize,
     pub world_foods: usize,
@@ -49,19 +175,45 @@ impl Default for Config {
             animal_size: 0.02,
             arc_size: 0.05,
             //
-            ga_reverse: 0,
+            ga_population: PopulationKind::RouletteWheel,
+            ga_fitness_transforms: vec![],
+            ga_constraints: vec![],
+            ga_constraint_handling: ConstraintHandling::FeasibilityFirst,
+            ga_fitness_aggregation: FitnessAggregation::Mean,
+            ga_reevaluate_elites: false,
             ga_mut_chance: 0.015,
             ga_mut_coeff: 0.3,
+            ga_mut_adaptation: MutationAdaptation::Fixed,
+            ga_mut_decay: MutationDecay::None,
+            ga_genealogy: false,
+            ga_genealogy_limit: 100_000,
+            ga_novelty_weight: 0.0,
+            ga_novelty_k: 15,
+            ga_novelty_threshold: 0.05,
+            ga_novelty_archive: 500,
+            ga_map_elites_bins: 0,
+            ga_optimizer: OptimizerKind::Genetic,
+            ga_optimizer_sigma: 0.1,
+            ga_optimizer_learning_rate: 0.03,
             //
             sim_speed_min: 0.001,
             sim_speed_max: 0.004,
             sim_speed_accel: 0.2,
             sim_rotation_accel: FRAC_PI_2,
             sim_generation_length: 2500,
+            sim_worlds: 1,
+            sim_threads: 1,
+            sim_starvation_ticks: 0,
+            sim_predators: 0,
+            sim_episodes: 1,
             //
             stun_duration: 15,
             stun_cooldown: 15,
             boost_cost: 0.015,
+            stun_penalty: 0.5,
+            //
+            stats_percentiles: vec![0.1, 0.9],
+            stats_histogram_bins: 10,
             //
             world_animals: 20,
             world_foods: 20,
@@ -0,0 +1,298 @@
+use crate::config::Config;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+/// Prefix of the environment variables read by `Config::apply_env`, e.g.
+/// `ANIMAL_SIM_GA_MUT_CHANCE=0.02`.
+pub const ENV_PREFIX: &str = "ANIMAL_SIM_";
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    /// Neither a `.toml` nor a `.json` file.
+    UnknownFormat(PathBuf),
+    UnknownKey(String),
+    /// An override that is not `key=value`, or whose value doesn't fit.
+    Override {
+        key: String,
+        message: String,
+    },
+    /// Every inconsistency found by `Config::validate`.
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "cannot read config: {err}"),
+            ConfigError::Toml(err) => write!(f, "malformed TOML config: {err}"),
+            ConfigError::Json(err) => write!(f, "malformed JSON config: {err}"),
+            ConfigError::UnknownFormat(path) => write!(
+                f,
+                "cannot tell the format of {}, expected .toml or .json",
+                path.display()
+            ),
+            ConfigError::UnknownKey(key) => write!(f, "unknown config key `{key}`"),
+            ConfigError::Override { key, message } => {
+                write!(f, "invalid override of `{key}`: {message}")
+            }
+            ConfigError::Invalid(problems) => {
+                write!(f, "invalid config: {}", problems.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(err) => Some(err),
+            ConfigError::Toml(err) => Some(err),
+            ConfigError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(err: io::Error) -> Self {
+        ConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        ConfigError::Toml(err)
+    }
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(err: serde_json::Error) -> Self {
+        ConfigError::Json(err)
+    }
+}
+
+impl Config {
+    /// Reads a TOML or JSON file, by its extension. Settings it leaves out
+    /// keep their defaults; unknown ones are rejected. The result is not
+    /// validated, so that overrides can still fix it.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let extension = path.extension().and_then(|e| e.to_str());
+        if !matches!(extension, Some("toml" | "json")) {
+            return Err(ConfigError::UnknownFormat(path.to_path_buf()));
+        }
+
+        let text = fs::read_to_string(path)?;
+        let settings: Value = if extension == Some("toml") {
+            toml::from_str(&text)?
+        } else {
+            serde_json::from_str(&text)?
+        };
+
+        let mut config = serde_json::to_value(Config::default())?;
+        match (&mut config, settings) {
+            (Value::Object(config), Value::Object(settings)) => {
+                if let Some(key) = settings.keys().find(|key| !config.contains_key(*key)) {
+                    return Err(ConfigError::UnknownKey(key.clone()));
+                }
+                config.extend(settings);
+            }
+            _ => unreachable!("configs are tables"),
+        }
+        Ok(serde_json::from_value(config)?)
+    }
+
+    /// Defaults, then the file, environment and `key=value` arguments in
+    /// turn, all validated. An argument ending in `.toml` or `.json`, or
+    /// without `=`, names the file.
+    pub fn from_args<S: AsRef<str>>(
+        args: impl IntoIterator<Item = S>,
+    ) -> Result<Self, ConfigError> {
+        let (files, overrides): (Vec<_>, Vec<_>) = args.into_iter().partition(|arg| {
+            let arg = arg.as_ref();
+            arg.ends_with(".toml") || arg.ends_with(".json") || !arg.contains('=')
+        });
+
+        let mut config = match files.as_slice() {
+            [] => Config::default(),
+            [file] => Config::from_file(file.as_ref())?,
+            _ => {
+                return Err(ConfigError::Override {
+                    key: files[1].as_ref().to_string(),
+                    message: "only one config file can be given".to_string(),
+                })
+            }
+        };
+        config.apply_env(std::env::vars())?;
+        config.apply_args(overrides)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Sets `key` from `value`, written as a TOML value. Bare words count
+    /// as strings, so `ga_optimizer=cma_es` works without quotes.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), ConfigError> {
+        let mut config = serde_json::to_value(&*self)?;
+        let slot = config
+            .get_mut(key)
+            .ok_or_else(|| ConfigError::UnknownKey(key.to_string()))?;
+
+        *slot = match toml::from_str::<toml::Table>(&format!("value = {value}")) {
+            Ok(mut table) => serde_json::to_value(table.remove("value"))?,
+            Err(_) => Value::String(value.to_string()),
+        };
+        *self = serde_json::from_value(config).map_err(|err| ConfigError::Override {
+            key: key.to_string(),
+            message: err.to_string(),
+        })?;
+        Ok(())
+    }
+
+    /// Applies `key=value` overrides in order.
+    pub fn apply_args<S: AsRef<str>>(
+        &mut self,
+        args: impl IntoIterator<Item = S>,
+    ) -> Result<(), ConfigError> {
+        for arg in args {
+            let arg = arg.as_ref();
+            let (key, value) = arg.split_once('=').ok_or_else(|| ConfigError::Override {
+                key: arg.to_string(),
+                message: "expected key=value".to_string(),
+            })?;
+            self.set(key.trim(), value.trim())?;
+        }
+        Ok(())
+    }
+
+    /// Applies the variables named `ENV_PREFIX` followed by an upper-case
+    /// key, ignoring all others.
+    pub fn apply_env(
+        &mut self,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<(), ConfigError> {
+        let mut vars: Vec<_> = vars
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let key = name.strip_prefix(ENV_PREFIX)?.to_lowercase();
+                Some((key, value))
+            })
+            .collect();
+        vars.sort();
+
+        for (key, value) in vars {
+            self.set(&key, &value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AnimalConstraint, OptimizerKind, PopulationKind};
+
+    #[test]
+    fn files() {
+        let dir = std::env::temp_dir();
+        let toml = dir.join(format!("animal_simulation_{}.toml", std::process::id()));
+        let json = toml.with_extension("json");
+        fs::write(
+            &toml,
+            "world_animals = 8\nga_optimizer = \"cma_es\"\nga_constraints = [{ max_boost_ratio = 0.2 }]\n",
+        )
+        .unwrap();
+        fs::write(&json, r#"{"world_animals": 8, "sim_worlds": 3}"#).unwrap();
+
+        let from_toml = Config::from_file(&toml).unwrap();
+        let from_json = Config::from_file(&json).unwrap();
+        fs::write(&json, r#"{"world_animal": 8}"#).unwrap();
+        let unknown = Config::from_file(&json);
+        fs::remove_file(&toml).unwrap();
+        fs::remove_file(&json).unwrap();
+
+        assert_eq!(from_toml.world_animals, 8);
+        assert_eq!(from_toml.ga_optimizer, OptimizerKind::CmaEs);
+        assert_eq!(
+            from_toml.ga_constraints,
+            [AnimalConstraint::MaxBoostRatio(0.2)]
+        );
+        assert_eq!(from_json.sim_worlds, 3);
+        assert_eq!(from_json.brain_neurons, Config::default().brain_neurons);
+        assert!(matches!(unknown, Err(ConfigError::UnknownKey(key)) if key == "world_animal"));
+        assert!(matches!(
+            Config::from_file("config.yaml"),
+            Err(ConfigError::UnknownFormat(_))
+        ));
+        assert!(matches!(
+            Config::from_file("missing.toml"),
+            Err(ConfigError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn overrides() {
+        let mut config = Config::default();
+        config
+            .apply_args([
+                "ga_mut_chance=0.5",
+                "ga_optimizer=one_plus_lambda",
+                "stats_percentiles=[0.5]",
+                "ga_population={ steady_state = { replacements = 4, policy = \"worst\" } }",
+            ])
+            .unwrap();
+        config
+            .apply_env([
+                ("ANIMAL_SIM_WORLD_ANIMALS".to_string(), "12".to_string()),
+                ("PATH".to_string(), "/bin".to_string()),
+            ])
+            .unwrap();
+
+        assert_eq!(config.ga_mut_chance, 0.5);
+        assert_eq!(config.ga_optimizer, OptimizerKind::OnePlusLambda);
+        assert_eq!(config.stats_percentiles, [0.5]);
+        assert!(matches!(
+            config.ga_population,
+            PopulationKind::SteadyState {
+                replacements: 4,
+                ..
+            }
+        ));
+        assert_eq!(config.world_animals, 12);
+
+        assert!(matches!(
+            config.set("world_animal", "3"),
+            Err(ConfigError::UnknownKey(_))
+        ));
+        assert!(matches!(
+            config.apply_args(["world_animals"]),
+            Err(ConfigError::Override { .. })
+        ));
+        assert!(matches!(
+            config.set("world_animals", "many"),
+            Err(ConfigError::Override { .. })
+        ));
+        assert_eq!(config.world_animals, 12);
+    }
+
+    #[test]
+    fn args() {
+        let dir = std::env::temp_dir()
+            .join(format!("animal_simulation_{}", std::process::id()))
+            .join("ga_mut_chance=0.1");
+        let toml = dir.join("config.toml");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&toml, "world_animals = 8\n").unwrap();
+
+        let config = Config::from_args([toml.to_str().unwrap(), "sim_worlds=3"]);
+        fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+
+        let config = config.unwrap();
+        assert_eq!(config.world_animals, 8);
+        assert_eq!(config.sim_worlds, 3);
+        assert_eq!(config.ga_mut_chance, Config::default().ga_mut_chance);
+    }
+}
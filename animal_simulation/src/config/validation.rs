@@ -0,0 +1,160 @@
+use crate::config::{Config, ConfigError, PopulationKind};
+use crate::*;
+
+impl Config {
+    /// Rejects settings that would make no sense or fail later on, listing
+    /// every problem found.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = vec![];
+        let mut check = |ok: bool, problem: String| {
+            if !ok {
+                problems.push(problem);
+            }
+        };
+
+        check(
+            self.brain_neurons > 0,
+            "brain_neurons must be positive".into(),
+        );
+        check(
+            self.eye_fov_range > 0.0,
+            format!("eye_fov_range must be positive, got {}", self.eye_fov_range),
+        );
+        check(
+            self.eye_fov_angle > 0.0 && self.eye_fov_angle <= 2.0 * PI,
+            format!(
+                "eye_fov_angle must be within (0, 2π], got {}",
+                self.eye_fov_angle
+            ),
+        );
+        check(self.eye_cells > 0, "eye_cells must be positive".into());
+        for (name, size) in [
+            ("food_size", self.food_size),
+            ("animal_size", self.animal_size),
+        ] {
+            check(size > 0.0, format!("{name} must be positive, got {size}"));
+        }
+
+        check(
+            (0.0..=1.0).contains(&self.ga_mut_chance),
+            format!(
+                "ga_mut_chance must be within 0..=1, got {}",
+                self.ga_mut_chance
+            ),
+        );
+        check(
+            self.ga_mut_coeff >= 0.0,
+            format!(
+                "ga_mut_coeff must not be negative, got {}",
+                self.ga_mut_coeff
+            ),
+        );
+        check(
+            self.ga_novelty_k > 0,
+            "ga_novelty_k must be positive".into(),
+        );
+        match self.ga_population {
+            PopulationKind::SteadyState { replacements, .. } => check(
+                replacements <= self.world_animals * self.sim_worlds.max(1),
+                format!("steady_state replacements ({replacements}) exceed the population"),
+            ),
+            PopulationKind::Alps {
+                layers, age_gap, ..
+            } => check(
+                layers > 0 && age_gap > 0,
+                "alps layers and age_gap must be positive".into(),
+            ),
+            PopulationKind::RouletteWheel | PopulationKind::Nsga2 => {}
+        }
+        if let FitnessAggregation::LowerConfidenceBound { .. } = self.ga_fitness_aggregation {
+            check(
+                self.sim_episodes >= 2,
+                format!(
+                    "lower_confidence_bound needs sim_episodes of at least 2, got {}",
+                    self.sim_episodes
+                ),
+            );
+        }
+        for transform in &self.ga_fitness_transforms {
+            if let FitnessTransform::Linear { multiplier } = *transform {
+                check(
+                    multiplier > 1.0,
+                    format!("linear scaling multiplier must exceed 1, got {multiplier}"),
+                );
+            }
+        }
+        match self.ga_constraint_handling {
+            ConstraintHandling::StochasticRanking { pf } => check(
+                (0.0..=1.0).contains(&pf),
+                format!("stochastic_ranking pf must be within 0..=1, got {pf}"),
+            ),
+            ConstraintHandling::AdaptivePenalty { factor, .. } => check(
+                factor > 0.0,
+                format!("adaptive_penalty factor must be positive, got {factor}"),
+            ),
+            _ => {}
+        }
+
+        check(
+            0.0 <= self.sim_speed_min && self.sim_speed_min <= self.sim_speed_max,
+            format!(
+                "sim_speed_min ({}) must be within 0..=sim_speed_max ({})",
+                self.sim_speed_min, self.sim_speed_max
+            ),
+        );
+        check(
+            self.sim_generation_length > 0,
+            "sim_generation_length must be positive".into(),
+        );
+        check(
+            self.world_animals > 0,
+            "world_animals must be positive".into(),
+        );
+        check(
+            self.stats_percentiles
+                .iter()
+                .all(|p| (0.0..=1.0).contains(p)),
+            "stats_percentiles must be within 0..=1".into(),
+        );
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        assert!(Config::default().validate().is_ok());
+
+        let config = Config {
+            sim_speed_min: 0.01,
+            sim_speed_max: 0.001,
+            eye_cells: 0,
+            ga_mut_chance: 1.5,
+            ga_fitness_aggregation: FitnessAggregation::LowerConfidenceBound { k: 1.0 },
+            ..Config::default()
+        };
+        let Err(ConfigError::Invalid(problems)) = config.validate() else {
+            panic!("accepted an invalid config");
+        };
+
+        assert_eq!(problems.len(), 4);
+        assert_eq!(problems[0], "eye_cells must be positive");
+        assert!(problems[1].starts_with("ga_mut_chance"));
+        assert!(problems[2].starts_with("lower_confidence_bound"));
+        assert!(problems[3].starts_with("sim_speed_min (0.01)"));
+
+        let config = Config {
+            ga_fitness_transforms: vec![FitnessTransform::Linear { multiplier: 1.0 }],
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
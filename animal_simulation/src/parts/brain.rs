@@ -1,26 +1,49 @@
-use crate::config::Config;
+use crate::checkpoint::BrainState;
+use crate::config::{Config, MutationAdaptation};
 use crate::*;
 use ndarray::{Array1, Array2};
-use std::cell::Cell;
-use std::iter::zip;
-use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// What evaluating a brain found out about it, kept behind a single lock.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Evaluation {
+    pub fitness: f32,
+    /// Fitness as transformed, penalised or mixed with novelty for the
+    /// next selection only; see `Individual::score`.
+    pub score: Option<f32>,
+    pub objectives: [f32; 3],
+    pub violations: Vec<f32>,
+    /// Fitness in every episode evaluated, see `ga_reevaluate_elites`.
+    pub samples: Vec<f32>,
+    /// Whether the brain has been placed in a world yet.
+    pub evaluated: bool,
+}
 
 #[derive(Debug)]
 pub struct Brain {
     speed_accel: f32,
     rotation_accel: f32,
-    pub fitness: Cell<f32>,
+    evaluation: Mutex<Evaluation>,
+    mutation_step: f32,
+    parent_fitness: Option<f32>,
+    pedigree: Pedigree,
     nn: Network,
 }
 
-impl Individual<Config> for Rc<Brain> {
+impl Individual<Config> for Arc<Brain> {
     fn random(params: &Config, rng: &mut dyn RngCore) -> Self {
         let nn = Network::random(&Brain::topology(params), rng);
-        Rc::new(Brain::new(params, nn))
+        Arc::new(Brain::new(params, nn, Pedigree::random(rng)))
     }
 
     fn fitness(&self) -> f32 {
-        self.fitness.get()
+        self.evaluation().fitness
+    }
+
+    fn score(&self) -> f32 {
+        let evaluation = self.evaluation();
+        evaluation.score.unwrap_or(evaluation.fitness)
     }
 
     fn mutate(self, params: &Config, rng: &mut dyn RngCore) -> Self {
@@ -28,6 +51,16 @@ impl Individual<Config> for Rc<Brain> {
             return self;
         }
 
+        let evaluated = self.evaluation().evaluated;
+        let pedigree = self.pedigree.mutant(evaluated, rng);
+
+        let coeff = if params.ga_mut_adaptation == MutationAdaptation::SelfAdaptive {
+            let rate = self_adaptation_rate(Network::parameter_count(&Brain::topology(params)));
+            self_adapt(self.mutation_step, rate, rng)
+        } else {
+            params.ga_mut_coeff
+        };
+
         let mut layers = vec![];
         for l1 in &self.nn.layers {
             let weights: Vec<f32> = l1
@@ -36,7 +69,7 @@ impl Individual<Config> for Rc<Brain> {
                 .map(|x1| {
                     if rng.gen_bool(params.ga_mut_chance as f64) {
                         let sign = if rng.gen_bool(0.5) { -1.0 } else { 1.0 };
-                        *x1 + sign * params.ga_mut_coeff * rng.gen::<f32>()
+                        *x1 + sign * coeff * rng.gen::<f32>()
                     } else {
                         *x1
                     }
@@ -50,7 +83,7 @@ impl Individual<Config> for Rc<Brain> {
                 .map(|x1| {
                     if rng.gen_bool(params.ga_mut_chance as f64) {
                         let sign = if rng.gen_bool(0.5) { -1.0 } else { 1.0 };
-                        *x1 + sign * params.ga_mut_coeff * rng.gen::<f32>()
+                        *x1 + sign * coeff * rng.gen::<f32>()
                     } else {
                         *x1
                     }
@@ -60,7 +93,11 @@ impl Individual<Config> for Rc<Brain> {
             layers.push(layer);
         }
         let nn = Network::new(layers);
-        Rc::new(Brain::new(params, nn))
+        Arc::new(Brain {
+            mutation_step: coeff,
+            parent_fitness: self.parent_fitness,
+            ..Brain::new(params, nn, pedigree)
+        })
     }
 
     fn crossover(&self, other: &Self, params: &Config, rng: &mut dyn RngCore) -> Self {
@@ -84,13 +121,102 @@ impl Individual<Config> for Rc<Brain> {
             layers.push(layer);
         }
         let nn = Network::new(layers);
-        Rc::new(Brain::new(params, nn))
+        // Keeps this parent's identity until `offspring` gives it its own.
+        Arc::new(Brain {
+            mutation_step: (self.mutation_step + other.mutation_step) / 2.0,
+            parent_fitness: Some((self.fitness() + other.fitness()) / 2.0),
+            ..Brain::new(params, nn, self.pedigree.clone())
+        })
+    }
+
+    /// Euclidean distance between the two networks' weights and biases.
+    fn distance(&self, other: &Self) -> Option<f32> {
+        let squared: f32 = self
+            .nn
+            .layers
+            .iter()
+            .zip(&other.nn.layers)
+            .flat_map(|(l1, l2)| {
+                l1.weights
+                    .iter()
+                    .zip(l2.weights.iter())
+                    .chain(l1.biases.iter().zip(l2.biases.iter()))
+            })
+            .map(|(x1, x2)| (x1 - x2).powi(2))
+            .sum();
+
+        Some(squared.sqrt())
+    }
+
+    fn pedigree(&self) -> Option<&Pedigree> {
+        Some(&self.pedigree)
+    }
+
+    fn set_pedigree(&mut self, pedigree: Pedigree) {
+        Arc::get_mut(self)
+            .expect("only fresh offspring get a pedigree")
+            .pedigree = pedigree;
+    }
+}
+
+impl MultiObjectiveIndividual<Config> for Arc<Brain> {
+    fn objectives(&self) -> Vec<f32> {
+        self.evaluation().objectives.to_vec()
+    }
+}
+
+impl ConstrainedIndividual<Config> for Arc<Brain> {
+    fn violations(&self) -> Vec<f32> {
+        self.evaluation().violations.clone()
     }
 }
 
 impl Brain {
-    pub(crate) fn from_network(config: &Config, network: Network) -> Self {
-        Self::new(config, network)
+    pub(crate) fn from_parameters(
+        config: &Config,
+        parameters: &[f32],
+        rng: &mut dyn RngCore,
+    ) -> Arc<Self> {
+        let nn = Network::from_parameters(&Self::topology(config), parameters);
+        Arc::new(Self::new(config, nn, Pedigree::random(rng)))
+    }
+
+    pub(crate) fn restore(config: &Config, state: &BrainState) -> Arc<Self> {
+        let nn = Network::from_parameters(&Self::topology(config), &state.parameters);
+        Arc::new(Self {
+            evaluation: Mutex::new(state.evaluation.clone()),
+            mutation_step: state.mutation_step,
+            parent_fitness: state.parent_fitness,
+            ..Self::new(config, nn, state.pedigree.clone())
+        })
+    }
+
+    pub(crate) fn snapshot(&self) -> BrainState {
+        BrainState {
+            parameters: self.parameters(),
+            evaluation: self.evaluation().clone(),
+            mutation_step: self.mutation_step,
+            parent_fitness: self.parent_fitness,
+            pedigree: self.pedigree.clone(),
+        }
+    }
+
+    pub fn evaluation(&self) -> MutexGuard<'_, Evaluation> {
+        self.evaluation.lock().unwrap()
+    }
+
+    pub(crate) fn parameters(&self) -> Vec<f32> {
+        self.nn.parameters()
+    }
+
+    pub(crate) fn mutation_step(&self) -> f32 {
+        self.mutation_step
+    }
+
+    /// Mean raw fitness of the parents this brain was bred from, as it was
+    /// when they were selected; selection scores never enter it.
+    pub(crate) fn parent_fitness(&self) -> Option<f32> {
+        self.parent_fitness
     }
 
     pub(crate) fn process(&self, inputs: Array1<f32>) -> (f32, f32, f32) {
@@ -107,12 +233,15 @@ impl Brain {
 }
 
 impl Brain {
-    fn new(config: &Config, nn: Network) -> Self {
+    fn new(config: &Config, nn: Network, pedigree: Pedigree) -> Self {
         Self {
             speed_accel: config.sim_speed_accel,
             rotation_accel: config.sim_rotation_accel,
             nn,
-            fitness: Cell::new(0.0),
+            evaluation: Mutex::default(),
+            mutation_step: config.ga_mut_coeff,
+            parent_fitness: None,
+            pedigree,
         }
     }
 
@@ -1,6 +1,5 @@
 use crate::config::Config;
 use crate::*;
-use std::ops::Deref;
 
 #[derive(Debug)]
 pub struct Eye {
@@ -145,10 +144,12 @@ mod tests {
                 na::Point2::new(self.x, self.y),
                 na::Rotation2::new(self.rot),
                 &self.foods,
+                &[],
             );
 
             let actual = actual
                 .into_iter()
+                .take(TEST_EYE_CELLS)
                 .map(|cell| {
                     if cell >= 0.7 {
                         "#"
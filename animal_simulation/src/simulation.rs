@@ -1,185 +1,1461 @@
-use crate::config::Config;
+use crate::checkpoint::{
+    write_atomically, BrainState, ChampionFile, ChampionState, Checkpoint, CheckpointError,
+    EliteState, CHECKPOINT_VERSION,
+};
+use crate::config::{Config, ConfigError, MutationAdaptation, OptimizerKind, PopulationKind};
 use crate::stats::Statistics;
-use crate::{Animal, Brain, VisibleAnimal, World};
-use genetic_algorithm::{Population, RouletteWheelPopulation};
-use rand::rngs::OsRng;
-use rand::{Rng, RngCore};
-use std::cell::Cell;
-use std::f32::consts::PI;
-use std::ops::{Deref, DerefMut};
-use std::rc::Rc;
+use crate::{Animal, Brain, Species, World};
+use genetic_algorithm::{
+    apply_fitness_transforms, arenas, arenas_mut, breed_preferred, hybrid_scores, offspring,
+    AlpsPopulation, BehaviourDimension, CmaEs, ConstrainedIndividual, ConstraintHandler,
+    DifferentialEvolution, Emitter, Evaluator, EvolutionStrategy, Genealogy, Individual,
+    MapElitesArchive, MutationRates, MutationSchedule, NaturalEvolutionStrategy, NoveltyArchive,
+    Nsga2Population, OneFifthRule, Optimizer, OptimizerState, Population, PopulationStatistics,
+    Preferences, RandomElitesEmitter, RouletteWheelPopulation, RunController, RunSummary,
+    SteadyStatePopulation,
+};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+
+type BrainPopulation = Box<dyn Population<Arc<Brain>, Config> + Send>;
+
+/// The coevolving populations sharing every world, by their index in it.
+const SPECIES: [Species; 2] = [Species::Prey, Species::Predator];
+const PREY: usize = 0;
+
+/// The fittest prey brain evaluated so far.
+#[derive(Clone, Debug)]
+pub struct Champion {
+    pub brain: Arc<Brain>,
+    pub fitness: f32,
+    pub generation: usize,
+}
+
+impl Champion {
+    /// Writes the brain along with `config`, which it needs to be rebuilt.
+    pub fn save(&self, config: &Config, path: impl AsRef<Path>) -> Result<(), CheckpointError> {
+        let file = ChampionFile {
+            version: CHECKPOINT_VERSION,
+            config: config.clone(),
+            champion: self.snapshot(),
+        };
+        write_atomically(path.as_ref(), &file)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<(Config, Self), CheckpointError> {
+        let value: serde_json::Value = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        let version = value["version"].as_u64().unwrap_or_default() as u32;
+        if version != CHECKPOINT_VERSION {
+            return Err(CheckpointError::Version(version));
+        }
+
+        let file: ChampionFile = serde_json::from_value(value)?;
+        let champion = Self::restore(&file.config, &file.champion);
+        Ok((file.config, champion))
+    }
+
+    fn snapshot(&self) -> ChampionState {
+        let mut brain = self.brain.snapshot();
+        brain.evaluation.fitness = self.fitness;
+
+        ChampionState {
+            brain,
+            fitness: self.fitness,
+            generation: self.generation,
+        }
+    }
+
+    fn restore(config: &Config, state: &ChampionState) -> Self {
+        Self {
+            brain: Brain::restore(config, &state.brain),
+            fitness: state.fitness,
+            generation: state.generation,
+        }
+    }
+}
 
 pub struct Simulation {
     config: Config,
-    world: World,
-    population: Box<RouletteWheelPopulation<Rc<Brain>, Config>>,
+    rng: ChaCha8Rng,
+    worlds: Vec<World>,
+    evaluator: Evaluator,
+    population: BrainPopulation,
+    predators: Option<RouletteWheelPopulation<Arc<Brain>>>,
+    novelty: NoveltyArchive,
+    elites: Option<MapElitesArchive<Arc<Brain>>>,
+    genealogy: Option<Genealogy>,
+    emitter: RandomElitesEmitter,
+    optimizer: Option<Box<dyn Optimizer + Send>>,
+    mutation_schedule: MutationSchedule,
+    one_fifth_rule: OneFifthRule,
+    constraints: [ConstraintHandler; 2],
     age: usize,
+    episode: usize,
+    champion: Option<Champion>,
     pub generation: usize,
-    pub rng: OsRng,
 }
 
 impl Simulation {
-    pub fn random(config: &Config) -> Self {
-        let rng = &mut OsRng;
-        let mut world = World::random(config, rng);
-        let population = Box::new(RouletteWheelPopulation::random(
-            config.world_animals,
+    /// # Panics
+    ///
+    /// If `config` does not pass `Config::validate`; see `try_random`.
+    pub fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+        Self::try_random(config, rng).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_random(config: &Config, rng: &mut dyn RngCore) -> Result<Self, ConfigError> {
+        Self::try_from_seed(config, rng.gen())
+    }
+
+    /// # Panics
+    ///
+    /// If `config` does not pass `Config::validate`; see `try_from_seed`.
+    pub fn from_seed(config: &Config, seed: u64) -> Self {
+        Self::try_from_seed(config, seed).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// A simulation driven entirely by a ChaCha generator seeded from
+    /// `seed`, so the same seed always gives the same run.
+    pub fn try_from_seed(config: &Config, seed: u64) -> Result<Self, ConfigError> {
+        config.validate()?;
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let seed = rng.gen();
+        let mut worlds: Vec<World> = (0..config.sim_worlds.max(1))
+            .map(|index| World::random(config, seed, index))
+            .collect();
+        let mut brains: Vec<_> = (0..config.world_animals * worlds.len())
+            .map(|_| Arc::<Brain>::random(config, &mut rng))
+            .collect();
+        let mut optimizer = Self::optimizer(config, &brains);
+        if let Some(optimizer) = &mut optimizer {
+            brains = optimizer
+                .ask(&mut rng)
+                .iter()
+                .map(|candidate| Brain::from_parameters(config, candidate, &mut rng))
+                .collect();
+        }
+        let predators = (config.sim_predators > 0).then(|| {
+            RouletteWheelPopulation::random(config.sim_predators * worlds.len(), config, &mut rng)
+        });
+        let population = Self::breeding_population(config, brains);
+        Self::populate(
             config,
-            rng,
-        ));
-        for individual in &population.population {
-            world
-                .animals
-                .push(Animal::from_brain(config, individual, rng))
+            &mut worlds,
+            &[
+                population.individuals(),
+                predators
+                    .as_ref()
+                    .map_or(&[], |predators| &predators.population),
+            ],
+        );
+
+        Ok(Self {
+            optimizer,
+            ..Self::new(config, rng, worlds, population, predators)
+        })
+    }
+
+    /// Writes everything needed to carry on exactly where this simulation
+    /// is, mid-generation included. The file is replaced atomically.
+    pub fn save_checkpoint(&self, path: impl AsRef<Path>) -> Result<(), CheckpointError> {
+        let optimizer = match &self.optimizer {
+            Some(optimizer) => Some(
+                optimizer
+                    .snapshot()
+                    .ok_or(CheckpointError::Unsupported("optimizer state"))?,
+            ),
+            None => None,
+        };
+        let population_state = self
+            .population
+            .snapshot()
+            .ok_or(CheckpointError::Unsupported("population state"))?
+            .map(|brain| brain.snapshot());
+
+        let brains: Vec<_> = self
+            .population()
+            .iter()
+            .chain(self.predators())
+            .cloned()
+            .collect();
+        let checkpoint = Checkpoint {
+            version: CHECKPOINT_VERSION,
+            config: self.config.clone(),
+            generation: self.generation,
+            age: self.age,
+            episode: self.episode,
+            rng: self.rng.clone(),
+            population: self
+                .population()
+                .iter()
+                .map(|brain| brain.snapshot())
+                .collect(),
+            population_state,
+            predators: self
+                .predators()
+                .iter()
+                .map(|brain| brain.snapshot())
+                .collect(),
+            worlds: self
+                .worlds
+                .iter()
+                .map(|world| world.snapshot(&brains))
+                .collect(),
+            novelty: self.novelty.behaviours().map(<[f32]>::to_vec).collect(),
+            elites: self
+                .elites
+                .iter()
+                .flat_map(MapElitesArchive::elites)
+                .map(|(_, elite)| EliteState {
+                    brain: elite.individual.snapshot(),
+                    fitness: elite.fitness,
+                    behaviour: elite.behaviour.clone(),
+                })
+                .collect(),
+            genealogy: self.genealogy.clone(),
+            one_fifth_step: self.one_fifth_rule.step(),
+            optimizer,
+            constraint_weights: self.constraints.each_ref().map(ConstraintHandler::weight),
+            champion: self.champion.as_ref().map(Champion::snapshot),
+        };
+
+        write_atomically(path.as_ref(), &checkpoint)
+    }
+
+    pub fn load_checkpoint(path: impl AsRef<Path>) -> Result<Self, CheckpointError> {
+        let value: serde_json::Value = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        let version = value["version"].as_u64().unwrap_or_default() as u32;
+        if version != CHECKPOINT_VERSION {
+            return Err(CheckpointError::Version(version));
         }
-        Self {
-            config: config.clone(),
-            world,
-            population,
-            age: 0,
-            generation: 0,
-            rng: *rng,
+
+        let checkpoint: Checkpoint = serde_json::from_value(value)?;
+        let config = checkpoint.config;
+        let optimizer = checkpoint.optimizer.map(OptimizerState::into_optimizer);
+        if optimizer.is_some() != (config.ga_optimizer != OptimizerKind::Genetic) {
+            return Err(CheckpointError::Unsupported("optimizer state"));
+        }
+
+        let restore = |brains: &[BrainState]| -> Vec<_> {
+            brains
+                .iter()
+                .map(|brain| Brain::restore(&config, brain))
+                .collect()
+        };
+        let mut population = Self::breeding_population(&config, restore(&checkpoint.population));
+        population.restore(
+            checkpoint
+                .population_state
+                .map(|brain| Brain::restore(&config, &brain)),
+        );
+        let predators = (config.sim_predators > 0)
+            .then(|| RouletteWheelPopulation::new(restore(&checkpoint.predators)));
+
+        let brains: Vec<_> = population
+            .individuals()
+            .iter()
+            .chain(predators.iter().flat_map(|predators| &predators.population))
+            .cloned()
+            .collect();
+        let worlds = checkpoint
+            .worlds
+            .iter()
+            .map(|world| World::restore(&config, world, &brains))
+            .collect();
+
+        let mut simulation = Self::new(&config, checkpoint.rng, worlds, population, predators);
+        simulation.age = checkpoint.age;
+        simulation.episode = checkpoint.episode;
+        simulation.generation = checkpoint.generation;
+        simulation.optimizer = optimizer;
+        simulation.genealogy = checkpoint.genealogy;
+        simulation.champion = checkpoint
+            .champion
+            .map(|champion| Champion::restore(&config, &champion));
+        simulation
+            .one_fifth_rule
+            .set_step(checkpoint.one_fifth_step);
+        for (constraints, weight) in simulation
+            .constraints
+            .iter_mut()
+            .zip(checkpoint.constraint_weights)
+        {
+            constraints.set_weight(weight);
+        }
+        for behaviour in checkpoint.novelty {
+            simulation.novelty.insert(behaviour);
+        }
+        if let Some(elites) = &mut simulation.elites {
+            for elite in checkpoint.elites {
+                let brain = Brain::restore(&config, &elite.brain);
+                elites.insert(brain, elite.fitness, elite.behaviour);
+            }
         }
+
+        Ok(simulation)
     }
 
     pub fn config(&self) -> &Config {
         &self.config
     }
 
+    /// The first world, whose prey are the first `world_animals` brains of
+    /// the population, in order.
     pub fn world(&self) -> &World {
-        &self.world
+        &self.worlds[0]
+    }
+
+    pub fn worlds(&self) -> &[World] {
+        &self.worlds
+    }
+
+    pub fn elites(&self) -> Option<&MapElitesArchive<Arc<Brain>>> {
+        self.elites.as_ref()
+    }
+
+    /// The brains being evaluated this generation.
+    pub fn population(&self) -> &[Arc<Brain>] {
+        self.population.individuals()
+    }
+
+    /// The predators' brains, empty unless `sim_predators` is set.
+    pub fn predators(&self) -> &[Arc<Brain>] {
+        self.predators
+            .as_ref()
+            .map_or(&[], |predators| &predators.population)
     }
 
-    pub fn step(&mut self, rng: &mut dyn RngCore) -> Option<Statistics> {
-        self.process_collisions(rng);
-        self.process_brains();
-        self.process_movements();
-        self.try_evolving(rng)
+    pub fn champion(&self) -> Option<&Champion> {
+        self.champion.as_ref()
     }
 
-    pub fn train(&mut self, rng: &mut dyn RngCore) -> Statistics {
+    /// Every brain evaluated so far, when `ga_genealogy` is set.
+    pub fn genealogy(&self) -> Option<&Genealogy> {
+        self.genealogy.as_ref()
+    }
+
+    pub fn step(&mut self) -> Option<Statistics> {
+        let arenas =
+            Self::world_shares_mut(&mut self.population, &mut self.predators, self.worlds.len());
+        for (world, mut arena) in self.worlds.iter_mut().zip(arenas) {
+            world.step(&self.config, self.age);
+            Self::replace_starved(&self.config, world, arena[PREY]);
+        }
+        self.try_evolving()
+    }
+
+    /// Runs the rest of the current generation, with every world on its own
+    /// thread when `sim_threads` allows it.
+    pub fn train(&mut self) -> Statistics {
         loop {
-            if let Some(statistics) = self.step(rng) {
-                return statistics;
+            let ticks = (self.config.sim_generation_length + 1).saturating_sub(self.age);
+            let (config, age) = (&self.config, self.age);
+            let arenas = Self::world_shares_mut(
+                &mut self.population,
+                &mut self.predators,
+                self.worlds.len(),
+            );
+            let mut worlds: Vec<_> = self.worlds.iter_mut().zip(arenas).collect();
+
+            self.evaluator.map_mut(&mut worlds, |_, (world, arena)| {
+                for tick in 0..ticks {
+                    world.step(config, age + tick);
+                    Self::replace_starved(config, world, arena[PREY]);
+                }
+            });
+
+            self.age += ticks;
+            if self.is_last_episode() {
+                return self.evolve(None);
             }
+            self.next_episode();
         }
     }
+
+    /// Like `step`, but reseeds the simulation's generator from `rng` first,
+    /// so that a seeded `rng` such as `seeded_rng(seed)` drives the run.
+    pub fn step_with(&mut self, rng: &mut dyn RngCore) -> Option<Statistics> {
+        self.rng = ChaCha8Rng::from_rng(rng).unwrap();
+        self.step()
+    }
+
+    /// Like `train`, but reseeds the simulation's generator from `rng` first.
+    pub fn train_with(&mut self, rng: &mut dyn RngCore) -> Statistics {
+        self.rng = ChaCha8Rng::from_rng(rng).unwrap();
+        self.train()
+    }
+
+    pub fn seeded_rng(seed: u64) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(seed)
+    }
+
+    /// Ends the current generation early, breeding the next one's prey from
+    /// `preferences` over `population()` rather than from their fitness.
+    pub fn evolve_interactively(&mut self, preferences: &Preferences) -> Statistics {
+        self.evolve(Some(preferences))
+    }
+
+    /// Ticks into the current episode.
+    pub fn age(&self) -> usize {
+        self.age
+    }
+
+    /// Trains until one of `controller`'s stop conditions holds.
+    pub fn run(&mut self, controller: &mut RunController<Statistics>) -> RunSummary {
+        controller.run(|| self.train())
+    }
 }
 
 impl Simulation {
-    fn process_collisions(&mut self, rng: &mut dyn RngCore) {
-        let visible_animals: Vec<VisibleAnimal> =
-            self.world.animals.iter().map(Animal::visible).collect();
-        for animal in &mut self.world.animals {
-            for food in &mut self.world.foods {
-                let distance = nalgebra::distance(&animal.position, &food.position);
-
-                if distance <= self.config.food_size {
-                    animal.satiation += 1;
-                    animal.brain.upgrade().unwrap().fitness.set(
-                        animal.satiation as f32 - animal.boosts as f32 * self.config.boost_cost,
-                    );
-                    food.position = rng.gen();
-                }
+    fn new(
+        config: &Config,
+        rng: ChaCha8Rng,
+        worlds: Vec<World>,
+        population: BrainPopulation,
+        predators: Option<RouletteWheelPopulation<Arc<Brain>>>,
+    ) -> Self {
+        Self {
+            config: config.clone(),
+            rng,
+            worlds,
+            evaluator: match config.sim_threads {
+                1 => Evaluator::sequential(),
+                threads => Evaluator::parallel(threads),
+            },
+            population,
+            predators,
+            novelty: NoveltyArchive::new(
+                config.ga_novelty_k,
+                config.ga_novelty_threshold,
+                config.ga_novelty_archive,
+            ),
+            elites: Self::map_elites(config),
+            genealogy: config.ga_genealogy.then(Genealogy::new),
+            emitter: RandomElitesEmitter::new(0.5),
+            optimizer: None,
+            mutation_schedule: MutationSchedule {
+                chance: config.ga_mut_decay.schedule(config.ga_mut_chance),
+                coeff: config.ga_mut_decay.schedule(config.ga_mut_coeff),
+            },
+            one_fifth_rule: OneFifthRule::new(config.ga_mut_coeff, 0.85),
+            constraints: std::array::from_fn(|_| {
+                ConstraintHandler::new(config.ga_constraint_handling)
+            }),
+            age: 0,
+            episode: 0,
+            champion: None,
+            generation: 0,
+        }
+    }
+
+    /// Replaces every animal that went `sim_starvation_ticks` without eating
+    /// with the offspring of two others from its world. The newborn's brain
+    /// takes the starved one's slot in `brains`, the world's share of the
+    /// population. Predators never eat, so they never starve either, and
+    /// neither do the candidates of an optimizer, which has to be told
+    /// about exactly the ones it asked for.
+    fn replace_starved(config: &Config, world: &mut World, brains: &mut [Arc<Brain>]) {
+        if config.sim_starvation_ticks == 0 || config.ga_optimizer != OptimizerKind::Genetic {
+            return;
+        }
+
+        let starved: Vec<usize> = (0..brains.len())
+            .filter(|&i| world.animals[i].hunger >= config.sim_starvation_ticks)
+            .collect();
+        if starved.is_empty() {
+            return;
+        }
+
+        let mut living: Vec<_> = (0..brains.len())
+            .filter(|i| !starved.contains(i))
+            .map(|i| Arc::clone(&brains[i]))
+            .collect();
+        if living.is_empty() {
+            living = brains.to_vec();
+        }
+        let parents = RouletteWheelPopulation::new(living);
+
+        for index in starved {
+            let parent_a = parents.select(config, &mut world.rng);
+            let parent_b = parents.select(config, &mut world.rng);
+            let child = offspring(parent_a, parent_b, config, &mut world.rng)
+                .mutate(config, &mut world.rng);
+
+            world.animals[index] =
+                Animal::from_brain(config, &child, Species::Prey, &mut world.rng);
+            world
+                .deaths
+                .push(std::mem::replace(&mut brains[index], child));
+        }
+    }
+
+    /// The population kind picked by `ga_population`, starting from `brains`.
+    fn breeding_population(config: &Config, brains: Vec<Arc<Brain>>) -> BrainPopulation {
+        match config.ga_population {
+            PopulationKind::RouletteWheel => Box::new(RouletteWheelPopulation::new(brains)),
+            PopulationKind::SteadyState {
+                replacements,
+                policy,
+            } => {
+                Box::new(SteadyStatePopulation::new(brains).with_replacement(replacements, policy))
             }
+            PopulationKind::Nsga2 => Box::new(Nsga2Population::new(brains)),
+            PopulationKind::Alps {
+                layers,
+                age_gap,
+                scheme,
+            } => Box::new(AlpsPopulation::new(brains).with_layers(layers, age_gap, scheme)),
+        }
+    }
 
-            for other_animal in &visible_animals {
-                let distance = nalgebra::distance(&animal.position, &other_animal.0);
-                let relative_angle = animal.rotation().angle_to(&other_animal.1);
-                if distance > 0.0000001
-                    && distance < self.config.animal_size
-                    && relative_angle > PI / 12f32
-                {
-                    let stunned = animal.stunned > 0;
-                    let boosting = animal.boosting;
-                    let other_stunned = other_animal.2 < 0.0000001;
-                    let other_boosting = other_animal.2 > self.config.sim_speed_max;
-
-                    if !other_stunned {
-                        if stunned {
-                            if animal.satiation >= 3 && other_boosting {
-                                animal.satiation -= 3;
-                            }
-                        } else {
-                            let mut stun_multiplier = 1u8;
-                            if boosting {
-                                //stun_multiplier += 1;
-                            }
-                            if other_boosting {
-                                stun_multiplier += 1;
-                            }
-                            animal.stun(self.config.stun_duration * stun_multiplier);
-                        }
-                    } else if !stunned && other_stunned && boosting && other_animal.3 >= 3 {
-                        animal.satiation += 3;
-                    }
+    /// Spreads the coevolving prey and predators over the worlds, each
+    /// world an arena with `world_animals` prey followed by `sim_predators`
+    /// predators.
+    fn populate(config: &Config, worlds: &mut [World], populations: &[&[Arc<Brain>]; 2]) {
+        let count = worlds.len();
+        for (world, arena) in worlds.iter_mut().zip(arenas(populations, count)) {
+            for (species, brains) in SPECIES.into_iter().zip(arena) {
+                for brain in brains {
+                    let animal = Animal::from_brain(config, brain, species, &mut world.rng);
+                    world.animals.push(animal);
                 }
             }
         }
     }
 
-    fn process_brains(&mut self) {
-        let visible_animals: Vec<VisibleAnimal> =
-            self.world.animals.iter().map(Animal::visible).collect();
-        for animal in &mut self.world.animals {
-            animal.process_brain(&self.config, &self.world.foods, &visible_animals, self.age);
+    /// Every world's share of the prey and the predators, in that order.
+    fn world_shares_mut<'a>(
+        population: &'a mut BrainPopulation,
+        predators: &'a mut Option<RouletteWheelPopulation<Arc<Brain>>>,
+        worlds: usize,
+    ) -> Vec<Vec<&'a mut [Arc<Brain>]>> {
+        let predators = predators
+            .as_mut()
+            .map_or(&mut [][..], |predators| &mut predators.population[..]);
+        arenas_mut(vec![population.individuals_mut(), predators], worlds)
+    }
+
+    fn animals(&self) -> impl Iterator<Item = &Animal> {
+        self.worlds.iter().flat_map(|world| world.animals.iter())
+    }
+
+    /// Leaves the raw fitness, which statistics report, as it is.
+    fn transform_fitness(config: &Config, brains: &[Arc<Brain>]) {
+        let mut scores: Vec<_> = brains.iter().map(|brain| brain.score()).collect();
+        apply_fitness_transforms(&config.ga_fitness_transforms, &mut scores);
+        Self::set_scores(brains, scores);
+    }
+
+    fn set_scores(brains: &[Arc<Brain>], scores: Vec<f32>) {
+        for (brain, score) in brains.iter().zip(scores) {
+            brain.evaluation().score = Some(score);
+        }
+    }
+
+    /// Once bred from, selection scores go back to being the raw fitness.
+    fn clear_scores(&self) {
+        for brain in self.population().iter().chain(self.predators()) {
+            brain.evaluation().score = None;
         }
     }
 
-    fn process_movements(&mut self) {
-        let config = &self.config;
-        for animal in &mut self.world.animals {
-            animal.process_movement(config);
+    fn handle_constraints(
+        handler: &mut ConstraintHandler,
+        brains: &[Arc<Brain>],
+        rng: &mut dyn RngCore,
+    ) {
+        let mut scores: Vec<_> = brains.iter().map(|brain| brain.score()).collect();
+        let violations: Vec<_> = brains.iter().map(|brain| brain.total_violation()).collect();
+        handler.apply(&mut scores, &violations, rng);
+        Self::set_scores(brains, scores);
+    }
+
+    /// Average speed against boost frequency, when `ga_map_elites_bins` is set.
+    fn map_elites(config: &Config) -> Option<MapElitesArchive<Arc<Brain>>> {
+        if config.ga_map_elites_bins == 0 {
+            return None;
+        }
+
+        Some(MapElitesArchive::new(vec![
+            BehaviourDimension::new(
+                config.sim_speed_min,
+                config.sim_speed_max * 2.0,
+                config.ga_map_elites_bins,
+            ),
+            BehaviourDimension::new(0.0, 1.0, config.ga_map_elites_bins),
+        ]))
+    }
+
+    /// Real-valued optimizer over the flattened brain weights, started from
+    /// the first random brain, unless `ga_optimizer` is `Genetic`.
+    fn optimizer(config: &Config, brains: &[Arc<Brain>]) -> Option<Box<dyn Optimizer + Send>> {
+        let initial = brains[0].parameters();
+        let lambda = brains.len();
+        let sigma = config.ga_optimizer_sigma;
+
+        Some(match config.ga_optimizer {
+            OptimizerKind::Genetic => return None,
+            OptimizerKind::CmaEs => Box::new(CmaEs::new(initial, sigma, lambda)),
+            OptimizerKind::OnePlusLambda => {
+                Box::new(EvolutionStrategy::one_plus_lambda(initial, lambda, sigma))
+            }
+            OptimizerKind::MuCommaLambda => Box::new(EvolutionStrategy::mu_comma_lambda(
+                initial,
+                (lambda / 4).max(1),
+                lambda,
+                sigma,
+            )),
+            OptimizerKind::NaturalEvolutionStrategy => Box::new(NaturalEvolutionStrategy::new(
+                initial,
+                sigma,
+                config.ga_optimizer_learning_rate,
+                lambda,
+            )),
+            OptimizerKind::DifferentialEvolution => {
+                Box::new(DifferentialEvolution::new(initial, sigma, lambda))
+            }
+        })
+    }
+
+    /// The mutation rates for the generation about to be bred.
+    fn adapt_mutation(&mut self) -> MutationRates {
+        let mut rates = self.mutation_schedule.rates(self.generation);
+        let brains = self.population.individuals();
+
+        match self.config.ga_mut_adaptation {
+            MutationAdaptation::Fixed => {}
+            MutationAdaptation::OneFifthRule => {
+                // Raw fitness on both sides, so that rescaled selection scores
+                // cannot pass for improvements.
+                for brain in brains {
+                    if let Some(parent_fitness) = brain.parent_fitness() {
+                        self.one_fifth_rule.record(brain.fitness() > parent_fitness);
+                    }
+                }
+                rates.coeff = self.one_fifth_rule.adapt();
+            }
+            MutationAdaptation::SelfAdaptive => {
+                rates.coeff = brains
+                    .iter()
+                    .map(|brain| brain.mutation_step())
+                    .sum::<f32>()
+                    / brains.len() as f32;
+            }
         }
+
+        rates
     }
 
-    fn try_evolving(&mut self, rng: &mut dyn RngCore) -> Option<Statistics> {
+    fn try_evolving(&mut self) -> Option<Statistics> {
         self.age += 1;
 
-        if self.age > self.config.sim_generation_length {
-            Some(self.evolve(rng))
+        if self.age <= self.config.sim_generation_length {
+            None
+        } else if self.is_last_episode() {
+            Some(self.evolve(None))
         } else {
+            self.next_episode();
             None
         }
     }
 
-    fn evolve(&mut self, rng: &mut dyn RngCore) -> Statistics {
+    fn is_last_episode(&self) -> bool {
+        self.episode + 1 >= self.config.sim_episodes
+    }
+
+    /// Evaluates the same brains once more in freshly seeded worlds.
+    fn next_episode(&mut self) {
+        self.record_samples();
+        self.age = 0;
+        self.episode += 1;
+        self.reset_worlds();
+    }
+
+    fn record_samples(&self) {
+        for animal in self.animals() {
+            let brain = animal.brain().upgrade().unwrap();
+            brain
+                .evaluation()
+                .samples
+                .push(animal.fitness(&self.config));
+        }
+    }
+
+    /// Sets every brain's fitness to the aggregate of its samples, which
+    /// are kept for the next generation only with `ga_reevaluate_elites`,
+    /// so that surviving brains are judged on all of their evaluations.
+    fn aggregate_samples(&self) {
+        self.record_samples();
+
+        let brains = self.population.individuals().iter().chain(self.predators());
+        for brain in brains {
+            let mut evaluation = brain.evaluation();
+            evaluation.fitness = self
+                .config
+                .ga_fitness_aggregation
+                .aggregate(&evaluation.samples);
+            if !self.config.ga_reevaluate_elites {
+                evaluation.samples.clear();
+            }
+        }
+    }
+
+    /// Before any fitness transform, so that generations compare fairly.
+    fn update_champion(&mut self) {
+        let best = self
+            .population
+            .individuals()
+            .iter()
+            .max_by(|a, b| a.fitness().total_cmp(&b.fitness()));
+        let Some(best) = best else {
+            return;
+        };
+
+        if self
+            .champion
+            .as_ref()
+            .is_none_or(|champion| best.fitness() > champion.fitness)
+        {
+            self.champion = Some(Champion {
+                brain: Arc::clone(best),
+                fitness: best.fitness(),
+                generation: self.generation,
+            });
+        }
+    }
+
+    fn reset_worlds(&mut self) {
+        let seed = self.rng.gen();
+        for (index, world) in self.worlds.iter_mut().enumerate() {
+            world.reset(seed, index);
+        }
+        Self::populate(
+            &self.config,
+            &mut self.worlds,
+            &[
+                self.population.individuals(),
+                self.predators
+                    .as_ref()
+                    .map_or(&[], |predators| &predators.population),
+            ],
+        );
+    }
+
+    fn evolve(&mut self, preferences: Option<&Preferences>) -> Statistics {
+        self.aggregate_samples();
+        self.update_champion();
         self.age = 0;
+        self.episode = 0;
         self.generation += 1;
 
-        if self.config.ga_reverse == 1 {
-            let max_satiation = self
-                .world
-                .animals
-                .iter()
-                .map(|animal| animal.satiation)
-                .max()
-                .unwrap_or_default();
+        for animal in self.animals() {
+            let brain = animal.brain().upgrade().unwrap();
+            let mut evaluation = brain.evaluation();
+            evaluation.objectives = animal.objectives(&self.config);
+            evaluation.violations = animal.violations(&self.config);
+        }
+
+        let animals: Vec<&Animal> = self
+            .worlds
+            .iter()
+            .flat_map(World::animals)
+            .filter(|animal| animal.species == Species::Prey)
+            .collect();
+
+        let deaths: Vec<_> = self
+            .worlds
+            .iter()
+            .flat_map(|world| world.deaths.iter().cloned())
+            .collect();
+        if let Some(genealogy) = &mut self.genealogy {
+            genealogy.record(&deaths, self.generation - 1);
+            genealogy.record(self.population.individuals(), self.generation - 1);
+            if let Some(predators) = &self.predators {
+                genealogy.record(&predators.population, self.generation - 1);
+            }
+            if genealogy.len() > self.config.ga_genealogy_limit {
+                let living: Vec<_> = self
+                    .population
+                    .individuals()
+                    .iter()
+                    .chain(self.predators.iter().flat_map(|p| &p.population))
+                    .chain(
+                        self.elites
+                            .iter()
+                            .flat_map(MapElitesArchive::elites)
+                            .map(|(_, elite)| &elite.individual),
+                    )
+                    .chain(self.champion.iter().map(|champion| &champion.brain))
+                    .filter_map(|brain| brain.pedigree())
+                    .map(|pedigree| pedigree.id)
+                    .collect();
+                genealogy.prune(&living);
+            }
+        }
 
-            for animal in &mut self.world.animals {
+        let map_elites = self.elites.as_mut().map(|elites| {
+            for animal in &animals {
                 let brain = animal.brain().upgrade().unwrap();
-                brain
-                    .fitness
-                    .set((max_satiation as f32) - brain.fitness.get());
+                let fitness = brain.fitness();
+                elites.insert(
+                    brain,
+                    fitness,
+                    vec![animal.average_speed(), animal.boost_ratio()],
+                );
+            }
+
+            elites.statistics()
+        });
+
+        if !self.config.ga_constraints.is_empty() {
+            let [prey, predators] = &mut self.constraints;
+            Self::handle_constraints(prey, self.population.individuals(), &mut self.rng);
+            if let Some(population) = &self.predators {
+                Self::handle_constraints(predators, &population.population, &mut self.rng);
             }
         }
 
-        self.world.animals.clear();
-        let (individuals, statistics) = self.population.evolve(self.config(), rng);
-        self.population = individuals;
-        for individual in &self.population.population {
-            self.world
-                .animals
-                .push(Animal::from_brain(self.config(), individual, rng))
+        if !self.config.ga_fitness_transforms.is_empty() {
+            Self::transform_fitness(&self.config, self.population());
+            Self::transform_fitness(&self.config, self.predators());
         }
 
-        for food in &mut self.world.foods {
-            food.position = rng.gen();
+        if self.config.ga_novelty_weight > 0.0 {
+            let novelties = self.novelty.evaluate(&animals);
+            let brains: Vec<_> = animals
+                .iter()
+                .map(|animal| animal.brain().upgrade().unwrap())
+                .collect();
+            let fitnesses: Vec<_> = brains.iter().map(|brain| brain.score()).collect();
+            let scores = hybrid_scores(&fitnesses, &novelties, self.config.ga_novelty_weight);
+            Self::set_scores(&brains, scores);
         }
 
+        let mutation_rates = self.adapt_mutation();
+        let mut params = self.config.clone();
+        params.ga_mut_chance = mutation_rates.chance;
+        if params.ga_mut_adaptation != MutationAdaptation::SelfAdaptive {
+            params.ga_mut_coeff = mutation_rates.coeff;
+        }
+
+        let statistics = match (preferences, &mut self.optimizer, &self.elites) {
+            (Some(preferences), _, _) => {
+                let brains = self.population.individuals();
+                let statistics = PopulationStatistics::new(brains);
+                let individuals = breed_preferred(brains, preferences, &params, &mut self.rng);
+                self.population.replace(individuals);
+
+                statistics
+            }
+            (None, Some(optimizer), _) => {
+                let brains = self.population.individuals();
+                let statistics = PopulationStatistics::new(brains);
+                let candidates: Vec<_> = brains.iter().map(|brain| brain.parameters()).collect();
+                let scores: Vec<_> = brains.iter().map(|brain| brain.score()).collect();
+                optimizer.tell(&candidates, &scores);
+
+                let individuals = optimizer
+                    .ask(&mut self.rng)
+                    .iter()
+                    .map(|candidate| Brain::from_parameters(&params, candidate, &mut self.rng))
+                    .collect();
+                self.population.replace(individuals);
+
+                statistics
+            }
+            (None, None, Some(elites)) => {
+                let statistics = PopulationStatistics::new(self.population.individuals());
+                let individuals = (0..self.population.size())
+                    .map(|_| self.emitter.emit(elites, &params, &mut self.rng))
+                    .collect();
+                self.population.replace(individuals);
+
+                statistics
+            }
+            (None, None, None) => self.population.evolve(&params, &mut self.rng),
+        };
+
+        let predators = self
+            .predators
+            .as_mut()
+            .map(|predators| predators.evolve(&params, &mut self.rng));
+
+        self.clear_scores();
+        self.reset_worlds();
+
         Statistics {
             generation: self.generation - 1,
-            ga: statistics,
+            ga: statistics
+                .with_mutation_rates(mutation_rates)
+                .with_percentiles(&self.config.stats_percentiles)
+                .with_histogram(self.config.stats_histogram_bins),
+            map_elites,
+            predators,
+            starved: deaths.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AnimalConstraint;
+    use crate::Evaluation;
+    use genetic_algorithm::{FitnessAggregation, FitnessTransform, Operator, StopCondition};
+
+    fn run(config: &Config, seed: u64, generations: usize) -> Vec<Statistics> {
+        let mut simulation = Simulation::from_seed(config, seed);
+
+        (0..generations).map(|_| simulation.train()).collect()
+    }
+
+    fn config() -> Config {
+        Config {
+            sim_generation_length: 50,
+            world_animals: 10,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn simulation_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Simulation>();
+    }
+
+    #[test]
+    fn invalid_configs_are_reported() {
+        let config = Config {
+            eye_cells: 0,
+            ..config()
+        };
+        assert!(matches!(
+            Simulation::try_from_seed(&config, 0),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn feasible_brains_rank_first() {
+        let config = Config {
+            ga_constraints: vec![AnimalConstraint::MaxBoostRatio(0.1)],
+            ..config()
+        };
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let brains: Vec<_> = [(3.0, 0.5), (1.0, 0.0), (2.0, 0.2), (0.5, 0.0)]
+            .iter()
+            .map(|&(fitness, violation)| {
+                let brain = Arc::<Brain>::random(&config, &mut rng);
+                *brain.evaluation() = Evaluation {
+                    fitness,
+                    violations: vec![violation],
+                    ..Evaluation::default()
+                };
+                brain
+            })
+            .collect();
+
+        let mut handler = ConstraintHandler::new(config.ga_constraint_handling);
+        Simulation::handle_constraints(&mut handler, &brains, &mut rng);
+        let scores: Vec<_> = brains.iter().map(|brain| brain.score()).collect();
+        assert_eq!(scores, [1.0, 4.0, 2.0, 3.0]);
+        assert_eq!(brains[0].fitness(), 3.0);
+    }
+
+    #[test]
+    fn selection_scores_leave_statistics_raw() {
+        let raw = run(&config(), 7, 1).remove(0).ga;
+        let transformed = Config {
+            ga_fitness_transforms: vec![FitnessTransform::Rank, FitnessTransform::Windowing],
+            ..config()
+        };
+        let constrained = Config {
+            ga_constraints: vec![AnimalConstraint::MaxBoostRatio(0.1)],
+            ..config()
+        };
+
+        let novel = Config {
+            ga_novelty_weight: 0.5,
+            ..config()
+        };
+
+        for config in [transformed, constrained, novel] {
+            let statistics = run(&config, 7, 1).remove(0).ga;
+            assert_eq!(statistics.max_fitness(), raw.max_fitness());
+            assert_eq!(statistics.avg_fitness(), raw.avg_fitness());
+        }
+    }
+
+    #[test]
+    fn one_fifth_rule_ignores_selection_scores() {
+        let config = Config {
+            ga_mut_adaptation: MutationAdaptation::OneFifthRule,
+            ..config()
+        };
+        let mut simulation = Simulation::from_seed(&config, 0);
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let parent = Arc::<Brain>::random(&config, &mut rng);
+        parent.evaluation().fitness = 1.0;
+        let children: Vec<_> = (0..10)
+            .map(|_| {
+                let child = parent.crossover(&parent, &config, &mut rng);
+                *child.evaluation() = Evaluation {
+                    fitness: 1.0,
+                    score: Some(10.0),
+                    ..Evaluation::default()
+                };
+                child
+            })
+            .collect();
+        simulation.population.replace(children);
+
+        assert!(simulation.adapt_mutation().coeff < config.ga_mut_coeff);
+    }
+
+    #[test]
+    fn mutation_adaptation_leaves_config() {
+        for adaptation in [
+            MutationAdaptation::OneFifthRule,
+            MutationAdaptation::SelfAdaptive,
+        ] {
+            let config = Config {
+                ga_mut_adaptation: adaptation,
+                ..config()
+            };
+            let mut simulation = Simulation::from_seed(&config, 0);
+            for _ in 0..3 {
+                simulation.train();
+            }
+
+            assert_eq!(simulation.config().ga_mut_coeff, config.ga_mut_coeff);
+            assert_eq!(simulation.config().ga_mut_chance, config.ga_mut_chance);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_statistics() {
+        let config = config();
+
+        assert_eq!(run(&config, 7, 3), run(&config, 7, 3));
+        assert_ne!(run(&config, 7, 3), run(&config, 8, 3));
+    }
+
+    #[test]
+    fn external_rng_drives_training() {
+        let config = config();
+        let run = |seed| {
+            let mut simulation = Simulation::from_seed(&config, 7);
+            let mut rng = Simulation::seeded_rng(seed);
+
+            (0..3)
+                .map(|_| simulation.train_with(&mut rng))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(run(1), run(1));
+        assert_ne!(run(1), run(2));
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let sequential = Config {
+            sim_worlds: 3,
+            sim_threads: 1,
+            ..config()
+        };
+        let parallel = Config {
+            sim_threads: 3,
+            ..sequential.clone()
+        };
+
+        assert_eq!(run(&sequential, 7, 3), run(&parallel, 7, 3));
+    }
+
+    #[test]
+    fn step_matches_train() {
+        let config = config();
+        let mut simulation = Simulation::from_seed(&config, 7);
+
+        let stepped: Vec<_> = (0..2)
+            .map(|_| loop {
+                if let Some(statistics) = simulation.step() {
+                    break statistics;
+                }
+            })
+            .collect();
+
+        assert_eq!(stepped, run(&config, 7, 2));
+    }
+
+    #[test]
+    fn episodes() {
+        let config = Config {
+            sim_episodes: 3,
+            ga_fitness_aggregation: FitnessAggregation::Min,
+            ..config()
+        };
+        let mut simulation = Simulation::from_seed(&config, 7);
+        let mut ticks = 0;
+        let stepped = loop {
+            ticks += 1;
+            if let Some(statistics) = simulation.step() {
+                break statistics;
+            }
+        };
+
+        assert_eq!(ticks, 3 * 51);
+        assert_eq!(vec![stepped], run(&config, 7, 1));
+        assert!(simulation
+            .population()
+            .iter()
+            .all(|brain| brain.evaluation().samples.is_empty()));
+    }
+
+    #[test]
+    fn reevaluated_elites_keep_samples() {
+        let config = Config {
+            sim_episodes: 2,
+            ga_reevaluate_elites: true,
+            ga_population: PopulationKind::SteadyState {
+                replacements: 3,
+                policy: genetic_algorithm::ReplacementPolicy::Worst,
+            },
+            ..config()
+        };
+        let mut simulation = Simulation::from_seed(&config, 7);
+        simulation.train();
+        simulation.train();
+
+        let mut samples: Vec<_> = simulation
+            .population()
+            .iter()
+            .map(|brain| brain.evaluation().samples.len())
+            .collect();
+        samples.sort();
+        // Three unevaluated children, two of the first generation's still
+        // alive, and the rest evaluated in both generations.
+        assert_eq!(samples, [0, 0, 0, 2, 2, 4, 4, 4, 4, 4]);
+    }
+
+    /// Checkpoints `config` mid-generation and checks that the resumed run
+    /// matches the original one.
+    fn assert_resumes(config: &Config) {
+        let path = std::env::temp_dir().join(format!(
+            "animal_simulation_checkpoint_{}_{:?}.json",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+
+        let mut simulation = Simulation::from_seed(config, 7);
+        simulation.train();
+        for _ in 0..20 {
+            simulation.step();
+        }
+        simulation.save_checkpoint(&path).unwrap();
+
+        let mut resumed = Simulation::load_checkpoint(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(resumed.generation, simulation.generation);
+        assert_eq!(
+            (0..2).map(|_| resumed.train()).collect::<Vec<_>>(),
+            (0..2).map(|_| simulation.train()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn resumes_from_checkpoint() {
+        assert_resumes(&Config {
+            ga_novelty_weight: 0.5,
+            ga_map_elites_bins: 4,
+            ..config()
+        });
+    }
+
+    #[test]
+    fn resumes_optimizers_from_checkpoint() {
+        for optimizer in [
+            OptimizerKind::CmaEs,
+            OptimizerKind::OnePlusLambda,
+            OptimizerKind::MuCommaLambda,
+            OptimizerKind::NaturalEvolutionStrategy,
+            OptimizerKind::DifferentialEvolution,
+        ] {
+            assert_resumes(&Config {
+                ga_optimizer: optimizer,
+                // Keeps CMA-ES's covariance matrix small.
+                brain_neurons: 2,
+                ..config()
+            });
         }
     }
+
+    #[test]
+    fn genealogy_records_every_brain() {
+        let config = Config {
+            ga_genealogy: true,
+            ..config()
+        };
+        let mut simulation = Simulation::from_seed(&config, 7);
+        for _ in 0..3 {
+            simulation.train();
+        }
+
+        let genealogy = simulation.genealogy().unwrap();
+        assert_eq!(genealogy.len(), 3 * config.world_animals);
+
+        for record in genealogy.records().filter(|record| record.generation > 0) {
+            assert!(record.parents.iter().all(|&parent| genealogy
+                .get(parent)
+                .is_some_and(|parent| parent.generation + 1 == record.generation)));
+        }
+    }
+
+    #[test]
+    fn genealogy_forgets_dead_lineages_past_its_limit() {
+        let config = Config {
+            ga_genealogy: true,
+            ga_genealogy_limit: 15,
+            ..config()
+        };
+        let mut simulation = Simulation::from_seed(&config, 7);
+        for _ in 0..5 {
+            simulation.train();
+        }
+
+        let genealogy = simulation.genealogy().unwrap();
+        assert!(genealogy.len() < 5 * config.world_animals);
+        for record in genealogy.records().filter(|record| record.generation > 0) {
+            assert!(record
+                .parents
+                .iter()
+                .all(|&parent| genealogy.get(parent).is_some()));
+        }
+    }
+
+    #[test]
+    fn mutating_a_crossover_child_is_one_birth() {
+        let config = Config {
+            ga_mut_chance: 0.0,
+            ..config()
+        };
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let a = Arc::<Brain>::random(&config, &mut rng);
+        let b = Arc::<Brain>::random(&config, &mut rng);
+        let operator = |brain: &Arc<Brain>| brain.pedigree().unwrap().operator;
+
+        let child = offspring(&a, &b, &config, &mut rng);
+        let mutant = Arc::clone(&child).mutate(&config, &mut rng);
+        assert_eq!(operator(&mutant), Operator::CrossoverMutation);
+        assert_eq!(mutant.pedigree().unwrap().id, child.pedigree().unwrap().id);
+
+        // Placed in a world, even one where it never eats, it counts as born.
+        Animal::from_brain(&config, &child, Species::Prey, &mut rng);
+        assert!(child.evaluation().evaluated);
+        let mutant = child.mutate(&config, &mut rng);
+        assert_eq!(operator(&mutant), Operator::Mutation);
+    }
+
+    #[test]
+    fn starved_animals_are_replaced() {
+        let config = Config {
+            sim_starvation_ticks: 10,
+            sim_worlds: 2,
+            ga_genealogy: true,
+            ..config()
+        };
+        let mut simulation = Simulation::from_seed(&config, 7);
+
+        while simulation.age < 40 {
+            simulation.step();
+        }
+        assert!(simulation.worlds.iter().all(|world| {
+            world
+                .animals
+                .iter()
+                .all(|animal| animal.brain().upgrade().is_some() && animal.hunger < 10)
+        }));
+
+        let statistics = simulation.train();
+        assert!(statistics.starved > 0);
+        assert_eq!(simulation.population().len(), 20);
+        assert_eq!(
+            simulation.genealogy().unwrap().len(),
+            20 + statistics.starved
+        );
+
+        let parallel = Config {
+            sim_threads: 2,
+            ..config.clone()
+        };
+        assert_eq!(run(&config, 7, 2), run(&parallel, 7, 2));
+    }
+
+    #[test]
+    fn optimizer_candidates_never_starve() {
+        let config = Config {
+            sim_starvation_ticks: 10,
+            ga_optimizer: OptimizerKind::CmaEs,
+            brain_neurons: 2,
+            ..config()
+        };
+        let mut simulation = Simulation::from_seed(&config, 7);
+        let candidates = simulation.population().to_vec();
+
+        while simulation.age < 40 {
+            simulation.step();
+        }
+        assert!(simulation
+            .population()
+            .iter()
+            .zip(&candidates)
+            .all(|(brain, candidate)| Arc::ptr_eq(brain, candidate)));
+        simulation.train();
+    }
+
+    #[test]
+    fn predators_coevolve() {
+        let config = Config {
+            sim_predators: 3,
+            sim_worlds: 2,
+            ..config()
+        };
+        let mut simulation = Simulation::from_seed(&config, 7);
+        let statistics = simulation.train();
+
+        assert!(statistics.predators.is_some());
+        assert_eq!(simulation.predators().len(), 6);
+        for world in simulation.worlds() {
+            let species: Vec<_> = world.animals().iter().map(Animal::species).collect();
+            assert_eq!(species[..10], [Species::Prey; 10]);
+            assert_eq!(species[10..], [Species::Predator; 3]);
+        }
+
+        let path = std::env::temp_dir().join(format!(
+            "animal_simulation_predators_{}.json",
+            std::process::id()
+        ));
+        for _ in 0..20 {
+            simulation.step();
+        }
+        simulation.save_checkpoint(&path).unwrap();
+        let mut resumed = Simulation::load_checkpoint(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(resumed.train(), simulation.train());
+
+        let parallel = Config {
+            sim_threads: 2,
+            ..config.clone()
+        };
+        assert_eq!(run(&config, 7, 2), run(&parallel, 7, 2));
+    }
+
+    #[test]
+    fn interactive_selection() {
+        let mut simulation = Simulation::from_seed(&config(), 7);
+        for _ in 0..10 {
+            simulation.step();
+        }
+
+        let mut preferences = Preferences::new();
+        preferences.toggle_favourite(3);
+        preferences.toggle_favourite(8);
+        let favourites: Vec<_> = [3, 8]
+            .map(|i| simulation.population()[i].pedigree().unwrap().id)
+            .to_vec();
+
+        simulation.evolve_interactively(&preferences);
+        assert_eq!(simulation.age(), 0);
+        assert_eq!(simulation.generation, 1);
+        for brain in simulation.population() {
+            let parents = &brain.pedigree().unwrap().parents;
+            assert!(!parents.is_empty());
+            assert!(parents.iter().all(|parent| favourites.contains(parent)));
+        }
+    }
+
+    #[test]
+    fn champion() {
+        let config = config();
+        let mut simulation = Simulation::from_seed(&config, 7);
+        assert!(simulation.champion().is_none());
+
+        let best: Vec<_> = (0..3)
+            .map(|_| simulation.train().ga.max_fitness())
+            .collect();
+        let champion = simulation.champion().unwrap();
+        let fittest = best.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        assert_eq!(champion.fitness, fittest);
+        assert_eq!(best[champion.generation], fittest);
+
+        let path = std::env::temp_dir().join(format!(
+            "animal_simulation_champion_{}.json",
+            std::process::id()
+        ));
+        champion.save(&config, &path).unwrap();
+        let (loaded_config, loaded) = Champion::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded_config.world_animals, config.world_animals);
+        assert_eq!(loaded.fitness, champion.fitness);
+        assert_eq!(loaded.generation, champion.generation);
+        assert_eq!(loaded.brain.parameters(), champion.brain.parameters());
+    }
+
+    #[test]
+    fn population_kind_from_config() {
+        for kind in [
+            PopulationKind::SteadyState {
+                replacements: 3,
+                policy: genetic_algorithm::ReplacementPolicy::Worst,
+            },
+            PopulationKind::Nsga2,
+            PopulationKind::Alps {
+                layers: 2,
+                age_gap: 3,
+                scheme: genetic_algorithm::AgingScheme::Linear,
+            },
+        ] {
+            let config = Config {
+                ga_population: kind,
+                ..config()
+            };
+            let mut simulation = Simulation::from_seed(&config, 7);
+            let before = simulation.population().to_vec();
+            simulation.train();
+
+            let survivors = simulation
+                .population()
+                .iter()
+                .filter(|brain| before.iter().any(|other| Arc::ptr_eq(brain, other)))
+                .count();
+            match kind {
+                PopulationKind::SteadyState { .. } => assert_eq!(survivors, 7),
+                _ => assert_eq!(survivors, 0),
+            }
+            assert_eq!(simulation.population().len(), 10);
+            assert_resumes(&config);
+        }
+    }
+
+    #[test]
+    fn run_until_stopped() {
+        let mut simulation = Simulation::from_seed(&config(), 7);
+        let mut generations = vec![];
+        let mut controller = RunController::new()
+            .stop_when(StopCondition::MaxGenerations(3))
+            .on_generation(|_, statistics: &Statistics| generations.push(statistics.generation));
+
+        let summary = simulation.run(&mut controller);
+        drop(controller);
+
+        assert_eq!(summary.reason, StopCondition::MaxGenerations(3));
+        assert_eq!(generations, [0, 1, 2]);
+        assert_eq!(simulation.generation, 3);
+    }
 }
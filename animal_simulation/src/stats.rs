@@ -1,10 +1,21 @@
 use crate::*;
 use std::fmt;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Statistics {
     pub generation: usize,
     pub ga: PopulationStatistics,
+    pub map_elites: Option<MapElitesStatistics>,
+    /// The predator population's, when there is one.
+    pub predators: Option<PopulationStatistics>,
+    /// Animals replaced mid-generation after starving.
+    pub starved: usize,
+}
+
+impl AsRef<PopulationStatistics> for Statistics {
+    fn as_ref(&self) -> &PopulationStatistics {
+        &self.ga
+    }
 }
 
 impl fmt::Display for Statistics {
@@ -12,11 +23,94 @@ impl fmt::Display for Statistics {
         writeln!(f, "generation {}:", self.generation)?;
         write!(
             f,
-            "min[{:.2}] max[{:.2}] avg[{:.2}] median[{:.2}]",
+            "min[{:.2}] max[{:.2}] avg[{:.2}] median[{:.2}] std[{:.2}]",
             self.ga.min_fitness(),
             self.ga.max_fitness(),
             self.ga.avg_fitness(),
-            self.ga.median_fitness()
-        )
+            self.ga.median_fitness(),
+            self.ga.std_dev_fitness()
+        )?;
+
+        if let Some(diversity) = self.ga.diversity() {
+            write!(f, " diversity[{:.2}]", diversity)?;
+        }
+
+        if !self.ga.percentiles().is_empty() {
+            writeln!(f)?;
+            let percentiles: Vec<_> = self
+                .ga
+                .percentiles()
+                .iter()
+                .map(|(p, fitness)| format!("p{}[{:.2}]", (p * 100.0).round(), fitness))
+                .collect();
+            write!(f, "{}", percentiles.join(" "))?;
+        }
+
+        if let Some(histogram) = self.ga.histogram() {
+            let counts: Vec<_> = histogram.counts().iter().map(usize::to_string).collect();
+            write!(
+                f,
+                "\nhistogram[{:.2}..{:.2}] {}",
+                histogram.min(),
+                histogram.max(),
+                counts.join(" ")
+            )?;
+        }
+
+        if let Some(selection) = self.ga.selection() {
+            write!(
+                f,
+                "\nparents[{}/{}] differential[{:.2}] intensity[{:.2}]",
+                selection.unique_parents(),
+                selection.selections(),
+                selection.differential(),
+                selection.intensity()
+            )?;
+        }
+
+        if let Some(rates) = self.ga.mutation_rates() {
+            write!(
+                f,
+                "\nmutation chance[{:.4}] coeff[{:.4}]",
+                rates.chance, rates.coeff
+            )?;
+        }
+
+        if let Some(predators) = &self.predators {
+            write!(
+                f,
+                "\npredators min[{:.2}] max[{:.2}] avg[{:.2}] median[{:.2}]",
+                predators.min_fitness(),
+                predators.max_fitness(),
+                predators.avg_fitness(),
+                predators.median_fitness()
+            )?;
+        }
+
+        if self.starved > 0 {
+            write!(f, "\nstarved[{}]", self.starved)?;
+        }
+
+        if let Some(map_elites) = &self.map_elites {
+            write!(
+                f,
+                "\nelites[{}/{}] coverage[{:.2}] qd[{:.2}] best[{:.2}]",
+                map_elites.filled(),
+                map_elites.capacity(),
+                map_elites.coverage(),
+                map_elites.qd_score(),
+                map_elites.max_fitness()
+            )?;
+        }
+
+        if let Some(pareto_front) = self.ga.pareto_front() {
+            write!(f, "\npareto front[{}]:", pareto_front.len())?;
+            for objectives in pareto_front {
+                let objectives: Vec<_> = objectives.iter().map(|o| format!("{:.2}", o)).collect();
+                write!(f, " ({})", objectives.join(", "))?;
+            }
+        }
+
+        Ok(())
     }
 }
@@ -3,14 +3,21 @@ mod food;
 
 pub use food::*;
 
+use crate::checkpoint::WorldState;
 use crate::config::Config;
 pub use crate::world::animal::*;
 use crate::*;
+use genetic_algorithm::task_rng;
+use rand_chacha::ChaCha8Rng;
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct World {
     pub(crate) animals: Vec<Animal>,
     pub(crate) foods: Vec<Food>,
+    pub(crate) rng: ChaCha8Rng,
+    /// Brains of the animals replaced this generation.
+    pub(crate) deaths: Vec<Arc<Brain>>,
 }
 
 impl World {
@@ -24,13 +31,169 @@ impl World {
 }
 
 impl World {
-    pub(crate) fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+    /// World number `index` of a batch seeded with `seed`.
+    pub fn random(config: &Config, seed: u64, index: usize) -> Self {
+        let mut rng = task_rng(seed, index);
         let animals = vec![];
 
         let foods = (0..config.world_foods)
-            .map(|_| Food::random(config, rng))
+            .map(|_| Food::random(config, &mut rng))
             .collect();
 
-        Self { animals, foods }
+        Self {
+            animals,
+            foods,
+            rng,
+            deaths: vec![],
+        }
+    }
+
+    /// Starts a new generation: the world gets a fresh generator, which
+    /// also places the new animals, and the food is scattered again.
+    pub(crate) fn reset(&mut self, seed: u64, index: usize) {
+        self.animals.clear();
+        self.deaths.clear();
+        self.rng = task_rng(seed, index);
+
+        for food in &mut self.foods {
+            food.position = self.rng.gen();
+        }
+    }
+
+    /// `brains` is the population, followed by the predators, that the
+    /// animals' brain indices refer to.
+    pub(crate) fn restore(config: &Config, state: &WorldState, brains: &[Arc<Brain>]) -> Self {
+        Self {
+            animals: state
+                .animals
+                .iter()
+                .map(|animal| Animal::restore(config, animal, &brains[animal.brain]))
+                .collect(),
+            foods: state
+                .foods
+                .iter()
+                .map(|&position| Food {
+                    position: position.into(),
+                })
+                .collect(),
+            rng: state.rng.clone(),
+            deaths: state
+                .deaths
+                .iter()
+                .map(|brain| Brain::restore(config, brain))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn snapshot(&self, brains: &[Arc<Brain>]) -> WorldState {
+        WorldState {
+            rng: self.rng.clone(),
+            deaths: self.deaths.iter().map(|brain| brain.snapshot()).collect(),
+            foods: self.foods.iter().map(|food| food.position.into()).collect(),
+            animals: self
+                .animals
+                .iter()
+                .map(|animal| {
+                    let brain = brains
+                        .iter()
+                        .position(|brain| animal.brain.ptr_eq(&Arc::downgrade(brain)))
+                        .expect("animal's brain is not in the population");
+                    animal.snapshot(brain)
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn step(&mut self, config: &Config, age: usize) {
+        self.process_collisions(config);
+        self.process_brains(config, age);
+        self.process_movements(config);
+    }
+}
+
+impl World {
+    fn process_collisions(&mut self, config: &Config) {
+        let visible_animals: Vec<VisibleAnimal> =
+            self.animals.iter().map(Animal::visible).collect();
+        let mut attackers = vec![];
+        for animal in &mut self.animals {
+            if animal.species == Species::Prey {
+                for food in &mut self.foods {
+                    let distance = nalgebra::distance(&animal.position, &food.position);
+
+                    if distance <= config.food_size {
+                        animal.satiation += 1;
+                        animal.hunger = 0;
+                        animal.update_fitness(config);
+                        food.position = self.rng.gen();
+                    }
+                }
+            }
+
+            for (attacker, other_animal) in visible_animals.iter().enumerate() {
+                let distance = nalgebra::distance(&animal.position, &other_animal.0);
+                let relative_angle = animal.rotation().angle_to(&other_animal.1);
+                if distance > 0.0000001
+                    && distance < config.animal_size
+                    && relative_angle > PI / 12f32
+                {
+                    let stunned = animal.stunned > 0;
+                    let boosting = animal.boosting;
+                    let other_stunned = other_animal.2 < 0.0000001;
+                    let other_boosting = other_animal.2 > config.sim_speed_max;
+
+                    if !other_stunned {
+                        if !other_animal.4.attacks(animal.species) {
+                            continue;
+                        }
+
+                        if stunned {
+                            if animal.satiation >= 3 && other_boosting {
+                                animal.satiation -= 3;
+                                animal.update_fitness(config);
+                            }
+                        } else {
+                            let mut stun_multiplier = 1u8;
+                            if boosting {
+                                //stun_multiplier += 1;
+                            }
+                            if other_boosting {
+                                stun_multiplier += 1;
+                            }
+                            if animal.stun(config.stun_duration * stun_multiplier) {
+                                attackers.push(attacker);
+                            }
+                        }
+                    } else if !stunned
+                        && other_stunned
+                        && boosting
+                        && other_animal.3 >= 3
+                        && animal.species.attacks(other_animal.4)
+                    {
+                        animal.satiation += 3;
+                        animal.update_fitness(config);
+                    }
+                }
+            }
+        }
+
+        for attacker in attackers {
+            self.animals[attacker].attacks += 1;
+            self.animals[attacker].update_fitness(config);
+        }
+    }
+
+    fn process_brains(&mut self, config: &Config, age: usize) {
+        let visible_animals: Vec<VisibleAnimal> =
+            self.animals.iter().map(Animal::visible).collect();
+        for animal in &mut self.animals {
+            animal.process_brain(config, &self.foods, &visible_animals, age);
+        }
+    }
+
+    fn process_movements(&mut self, config: &Config) {
+        for animal in &mut self.animals {
+            animal.process_movement(config);
+        }
     }
 }
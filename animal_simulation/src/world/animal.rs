@@ -1,15 +1,36 @@
+use crate::checkpoint::AnimalState;
 use crate::config::Config;
 use crate::*;
 use ndarray::Array1;
-use std::rc::{Rc, Weak};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Weak};
 
 pub struct VisibleAnimal(
     pub na::Point2<f32>,
     pub na::Rotation2<f32>,
     pub f32,
     pub usize,
+    pub Species,
 );
 
+/// Which of the coevolving populations an animal's brain belongs to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Species {
+    /// Eats food, and stuns and robs other prey it runs into.
+    #[default]
+    Prey,
+    /// Never eats nor gets stunned; lives off stunning and robbing prey.
+    Predator,
+}
+
+impl Species {
+    /// Whether this species stuns and robs `victim`; only prey fall victim.
+    pub fn attacks(self, victim: Species) -> bool {
+        victim == Species::Prey
+    }
+}
+
 #[derive(Debug)]
 pub struct Animal {
     pub(crate) position: na::Point2<f32>,
@@ -18,11 +39,23 @@ pub struct Animal {
     pub(crate) speed: f32,
     pub(crate) eye: Eye,
     pub(crate) brain: Weak<Brain>,
+    pub(crate) species: Species,
     pub satiation: usize,
     pub boosts: usize,
     pub boosting: bool,
     pub stunned: u8,
     pub stunned_cooldown: u8,
+    pub stuns: usize,
+    /// Stuns dealt to other animals.
+    pub attacks: usize,
+    /// Ticks since this animal last ate.
+    pub hunger: usize,
+    pub(crate) ticks: usize,
+    pub(crate) speed_sum: f32,
+    /// Sums of the cosines and sines of the coordinates taken as angles
+    /// around the torus, see `position_spread`.
+    pub(crate) position_cos_sum: na::Vector2<f32>,
+    pub(crate) position_sin_sum: na::Vector2<f32>,
 }
 
 impl Animal {
@@ -38,32 +71,168 @@ impl Animal {
         &self.vision
     }
 
+    pub fn species(&self) -> Species {
+        self.species
+    }
+
     pub fn visible(&self) -> VisibleAnimal {
-        VisibleAnimal(self.position, self.rotation, self.speed, self.satiation)
+        VisibleAnimal(
+            self.position,
+            self.rotation,
+            self.speed,
+            self.satiation,
+            self.species,
+        )
+    }
+
+    /// Satiation less the boost cost; predators also score every stun they
+    /// deal, while prey hunted by predators pay `stun_penalty` for every stun
+    /// they take.
+    pub fn fitness(&self, config: &Config) -> f32 {
+        let fitness = self.satiation as f32 - self.boosts as f32 * config.boost_cost;
+        match self.species {
+            Species::Prey if config.sim_predators > 0 => {
+                fitness - self.stuns as f32 * config.stun_penalty
+            }
+            Species::Prey => fitness,
+            Species::Predator => fitness + self.attacks as f32,
+        }
+    }
+
+    /// How far the animal strayed from its mean position. The world wraps
+    /// around, so every axis takes the circular variance `1 - R` of its
+    /// coordinate, scaled to match the plain variance of a tight cluster.
+    pub fn position_spread(&self) -> f32 {
+        if self.ticks == 0 {
+            return 0.0;
+        }
+
+        let ticks = self.ticks as f32;
+        let variance = self
+            .position_cos_sum
+            .zip_map(&self.position_sin_sum, |cos, sin| {
+                let resultant = (cos * cos + sin * sin).sqrt() / ticks;
+                (1.0 - resultant).max(0.0) / (2.0 * PI * PI)
+            });
+
+        variance.sum().sqrt()
+    }
+
+    pub fn average_speed(&self) -> f32 {
+        self.speed_sum / self.ticks.max(1) as f32
+    }
+
+    pub fn boost_ratio(&self) -> f32 {
+        self.boosts as f32 / self.ticks.max(1) as f32
+    }
+
+    pub fn stun_ratio(&self) -> f32 {
+        self.stuns as f32 / self.ticks.max(1) as f32
+    }
+
+    /// Satiation, boost cost and stuns taken, all as objectives to maximize.
+    pub fn objectives(&self, config: &Config) -> [f32; 3] {
+        [
+            self.satiation as f32,
+            -(self.boosts as f32) * config.boost_cost,
+            -(self.stuns as f32),
+        ]
+    }
+
+    /// Violation of every one of `ga_constraints`.
+    pub fn violations(&self, config: &Config) -> Vec<f32> {
+        config
+            .ga_constraints
+            .iter()
+            .map(|constraint| constraint.violation(self))
+            .collect()
+    }
+}
+
+impl BehaviourDescriptor for Animal {
+    fn behaviour(&self) -> Vec<f32> {
+        vec![
+            self.position_spread(),
+            self.boost_ratio(),
+            self.stun_ratio(),
+        ]
     }
 }
 
 impl Animal {
-    pub(crate) fn random(config: &Config, brain: &Rc<Brain>, rng: &mut dyn RngCore) -> Self {
-        //let brain = Brain::random(config, rng);
+    pub(crate) fn from_brain(
+        config: &Config,
+        brain: &Arc<Brain>,
+        species: Species,
+        rng: &mut dyn RngCore,
+    ) -> Self {
+        Self::new(config, brain, species, rng)
+    }
 
-        Self::new(config, brain, rng)
+    pub(crate) fn restore(config: &Config, state: &AnimalState, brain: &Arc<Brain>) -> Self {
+        Self {
+            position: state.position.into(),
+            rotation: na::Rotation2::new(state.rotation),
+            vision: state.vision.clone(),
+            speed: state.speed,
+            eye: Eye::new(config),
+            brain: Arc::downgrade(brain),
+            species: state.species,
+            satiation: state.satiation,
+            boosts: state.boosts,
+            boosting: state.boosting,
+            stunned: state.stunned,
+            stunned_cooldown: state.stunned_cooldown,
+            stuns: state.stuns,
+            attacks: state.attacks,
+            hunger: state.hunger,
+            ticks: state.ticks,
+            speed_sum: state.speed_sum,
+            position_cos_sum: state.position_cos_sum.into(),
+            position_sin_sum: state.position_sin_sum.into(),
+        }
     }
 
-    pub(crate) fn from_brain(config: &Config, brain: &Rc<Brain>, rng: &mut dyn RngCore) -> Self {
-        Self::new(config, brain, rng)
+    /// `brain` is the index of this animal's brain in the population.
+    pub(crate) fn snapshot(&self, brain: usize) -> AnimalState {
+        AnimalState {
+            brain,
+            species: self.species,
+            position: self.position.into(),
+            rotation: self.rotation.angle(),
+            vision: self.vision.clone(),
+            speed: self.speed,
+            satiation: self.satiation,
+            boosts: self.boosts,
+            boosting: self.boosting,
+            stunned: self.stunned,
+            stunned_cooldown: self.stunned_cooldown,
+            stuns: self.stuns,
+            attacks: self.attacks,
+            hunger: self.hunger,
+            ticks: self.ticks,
+            speed_sum: self.speed_sum,
+            position_cos_sum: self.position_cos_sum.into(),
+            position_sin_sum: self.position_sin_sum.into(),
+        }
     }
 
     pub(crate) fn brain(&self) -> Weak<Brain> {
         Weak::clone(&self.brain)
     }
 
+    pub(crate) fn update_fitness(&self, config: &Config) {
+        let brain = self.brain.upgrade().unwrap();
+        let mut evaluation = brain.evaluation();
+        evaluation.fitness = self.fitness(config);
+    }
+
     pub(crate) fn process_brain(
         &mut self,
         config: &Config,
         foods: &[Food],
         animals: &[VisibleAnimal],
-        age: usize,
+        _age: usize,
     ) {
         if self.stunned > 0 {
             return;
@@ -99,6 +268,13 @@ impl Animal {
     }
 
     pub(crate) fn process_movement(&mut self, config: &Config) {
+        self.ticks += 1;
+        self.hunger += 1;
+        self.speed_sum += self.speed;
+        let angles = self.position.coords * TAU;
+        self.position_cos_sum += angles.map(f32::cos);
+        self.position_sin_sum += angles.map(f32::sin);
+
         if self.stunned == 0 {
             self.position += self.rotation * na::Vector2::new(0.0, self.speed);
             self.position.x = na::wrap(self.position.x, 0.0, 1.0);
@@ -114,32 +290,104 @@ impl Animal {
         }
     }
 
-    pub(crate) fn stun(&mut self, duration: u8) {
+    /// Whether the stun took, which it cannot during a stun or its cooldown.
+    pub(crate) fn stun(&mut self, duration: u8) -> bool {
         if self.stunned == 0 && self.stunned_cooldown == 0 {
             self.stunned = duration;
+            self.stuns += 1;
             self.boosting = false;
             self.speed = 0.0;
             if self.satiation > 0 {
                 //self.satiation -= 1;
             }
+            true
+        } else {
+            false
         }
     }
 }
 
 impl Animal {
-    fn new(config: &Config, brain: &Rc<Brain>, rng: &mut dyn RngCore) -> Self {
+    fn new(config: &Config, brain: &Arc<Brain>, species: Species, rng: &mut dyn RngCore) -> Self {
+        brain.evaluation().evaluated = true;
+
         Self {
             position: rng.gen(),
             rotation: rng.gen(),
             vision: vec![0.0; config.eye_cells],
             speed: config.sim_speed_max,
             eye: Eye::new(config),
-            brain: Rc::downgrade(&Rc::clone(brain)),
+            brain: Arc::downgrade(brain),
+            species,
             satiation: 0,
             boosts: 0,
             boosting: false,
             stunned: 0,
             stunned_cooldown: 0,
+            stuns: 0,
+            attacks: 0,
+            hunger: 0,
+            ticks: 0,
+            speed_sum: 0.0,
+            position_cos_sum: na::Vector2::zeros(),
+            position_sin_sum: na::Vector2::zeros(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn position_spread_wraps_around() {
+        let config = Config::default();
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let brain = Arc::<Brain>::random(&config, &mut rng);
+        let mut animal = Animal::new(&config, &brain, Species::Prey, &mut rng);
+        animal.speed = 0.0;
+
+        // Hovering either side of the edge is hardly moving at all.
+        for x in [0.995, 0.005, 0.995, 0.005] {
+            animal.position = na::Point2::new(x, 0.5);
+            animal.process_movement(&config);
+        }
+        assert_relative_eq!(animal.position_spread(), 0.005, epsilon = 1e-4);
+
+        for x in [0.4, 0.6, 0.4, 0.6] {
+            animal.position = na::Point2::new(x, 0.5);
+            animal.process_movement(&config);
+        }
+        assert!(animal.position_spread() > 0.05);
+    }
+
+    #[test]
+    fn prey_pay_for_stuns_taken() {
+        let config = Config {
+            stun_penalty: 0.5,
+            sim_predators: 1,
+            ..Config::default()
+        };
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let brain = Arc::<Brain>::random(&config, &mut rng);
+        let mut prey = Animal::new(&config, &brain, Species::Prey, &mut rng);
+        let mut predator = Animal::new(&config, &brain, Species::Predator, &mut rng);
+
+        for animal in [&mut prey, &mut predator] {
+            animal.satiation = 2;
+            assert!(animal.stun(config.stun_duration));
         }
+
+        assert_eq!(prey.fitness(&config), 1.5);
+        assert_eq!(predator.fitness(&config), 2.0);
+
+        let without_predators = Config {
+            sim_predators: 0,
+            ..config
+        };
+        assert_eq!(prey.fitness(&without_predators), 2.0);
     }
 }
@@ -13,7 +13,7 @@ impl Food {
 }
 
 impl Food {
-    pub(crate) fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+    pub(crate) fn random(_config: &Config, rng: &mut dyn RngCore) -> Self {
         Self {
             position: rng.gen(),
         }
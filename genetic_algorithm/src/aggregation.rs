@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+/// How repeated, noisy evaluations of an individual combine into its
+/// fitness.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FitnessAggregation {
+    #[default]
+    Mean,
+    Median,
+    /// The worst evaluation, for individuals that must do well every time.
+    Min,
+    /// The mean less `k` standard errors, which favours individuals that
+    /// have been evaluated often and consistently. A single evaluation has
+    /// no error to speak of, so it needs at least two to mean anything.
+    LowerConfidenceBound {
+        k: f32,
+    },
+}
+
+impl FitnessAggregation {
+    /// Zero for no samples at all.
+    pub fn aggregate(&self, samples: &[f32]) -> f32 {
+        if samples.is_empty() {
+            return 0.0;
+        }
+
+        let n = samples.len() as f32;
+        let mean = samples.iter().sum::<f32>() / n;
+
+        match *self {
+            FitnessAggregation::Mean => mean,
+            FitnessAggregation::Median => {
+                let mut sorted = samples.to_vec();
+                sorted.sort_by(f32::total_cmp);
+
+                let middle = sorted.len() / 2;
+                if sorted.len().is_multiple_of(2) {
+                    (sorted[middle - 1] + sorted[middle]) / 2.0
+                } else {
+                    sorted[middle]
+                }
+            }
+            FitnessAggregation::Min => samples.iter().copied().fold(f32::INFINITY, f32::min),
+            FitnessAggregation::LowerConfidenceBound { k } => {
+                if samples.len() < 2 {
+                    return mean;
+                }
+
+                let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f32>() / (n - 1.0);
+                mean - k * (variance / n).sqrt()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn aggregate() {
+        let samples = [4.0, 1.0, 7.0, 4.0];
+
+        assert_eq!(FitnessAggregation::Mean.aggregate(&samples), 4.0);
+        assert_eq!(FitnessAggregation::Median.aggregate(&samples), 4.0);
+        assert_eq!(FitnessAggregation::Median.aggregate(&samples[..3]), 4.0);
+        assert_eq!(FitnessAggregation::Median.aggregate(&samples[1..3]), 4.0);
+        assert_eq!(FitnessAggregation::Min.aggregate(&samples), 1.0);
+        assert_eq!(FitnessAggregation::Min.aggregate(&[]), 0.0);
+
+        // Variance 6, standard error sqrt(6 / 4).
+        assert_relative_eq!(
+            FitnessAggregation::LowerConfidenceBound { k: 2.0 }.aggregate(&samples),
+            4.0 - 2.0 * 1.5f32.sqrt()
+        );
+        assert_eq!(
+            FitnessAggregation::LowerConfidenceBound { k: 2.0 }.aggregate(&[3.0]),
+            3.0
+        );
+    }
+}
@@ -0,0 +1,144 @@
+mod problems;
+
+use crate::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::fmt;
+
+pub use problems::*;
+
+/// A problem with a known optimum, to compare how populations and their
+/// operators fare on it.
+#[derive(Clone)]
+pub struct Benchmark<C> {
+    pub name: &'static str,
+    pub config: C,
+    /// Best fitness attainable.
+    pub optimum: f32,
+    /// Fitness at which a run counts as solved.
+    pub target: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchmarkRun {
+    pub seed: u64,
+    pub generations: usize,
+    pub best_fitness: f32,
+    /// Evaluations until the target was reached, if it was.
+    pub evaluations_to_solution: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchmarkReport {
+    pub name: &'static str,
+    pub runs: Vec<BenchmarkRun>,
+}
+
+impl<C: Clone> Benchmark<C> {
+    /// Evolves a population of type `P` once per seed, until the target is
+    /// reached or `max_generations` pass. Every generation costs one
+    /// evaluation per individual.
+    pub fn run<P, I>(
+        &self,
+        population_size: usize,
+        max_generations: usize,
+        seeds: impl IntoIterator<Item = u64>,
+    ) -> BenchmarkReport
+    where
+        P: Population<I, C>,
+        I: Individual<C>,
+    {
+        let runs = seeds
+            .into_iter()
+            .map(|seed| {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                let mut population = P::random(population_size, &self.config, &mut rng);
+
+                let summary = RunController::new()
+                    .stop_when(StopCondition::TargetFitness(self.target))
+                    .stop_when(StopCondition::MaxGenerations(max_generations))
+                    .run(|| population.evolve(&self.config, &mut rng));
+
+                let solved = summary.best_fitness >= self.target;
+                BenchmarkRun {
+                    seed,
+                    generations: summary.generations,
+                    best_fitness: summary.best_fitness,
+                    evaluations_to_solution: solved
+                        .then_some(summary.generations * population_size),
+                }
+            })
+            .collect();
+
+        BenchmarkReport {
+            name: self.name,
+            runs,
+        }
+    }
+}
+
+impl BenchmarkReport {
+    pub fn success_rate(&self) -> f32 {
+        self.solved().count() as f32 / self.runs.len().max(1) as f32
+    }
+
+    /// Mean evaluations to solution over the solved runs.
+    pub fn mean_evaluations(&self) -> Option<f32> {
+        let solved: Vec<_> = self.solved().collect();
+        (!solved.is_empty()).then(|| solved.iter().sum::<usize>() as f32 / solved.len() as f32)
+    }
+
+    pub fn mean_best_fitness(&self) -> f32 {
+        self.runs.iter().map(|run| run.best_fitness).sum::<f32>() / self.runs.len().max(1) as f32
+    }
+
+    fn solved(&self) -> impl Iterator<Item = usize> + '_ {
+        self.runs
+            .iter()
+            .filter_map(|run| run.evaluations_to_solution)
+    }
+}
+
+impl fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: solved {}/{} ({:.0}%), best {:.4}",
+            self.name,
+            self.solved().count(),
+            self.runs.len(),
+            self.success_rate() * 100.0,
+            self.mean_best_fitness(),
+        )?;
+
+        if let Some(evaluations) = self.mean_evaluations() {
+            write!(f, ", {evaluations:.0} evaluations to solution")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_one_max() {
+        let report = one_max(16).run::<RouletteWheelPopulation<_>, BitString>(50, 200, 0..4);
+
+        assert_eq!(report.runs.len(), 4);
+        assert_eq!(report.success_rate(), 1.0);
+        for run in &report.runs {
+            assert_eq!(run.evaluations_to_solution, Some(run.generations * 50));
+        }
+
+        let unsolved = Benchmark {
+            target: 17.0,
+            ..one_max(16)
+        }
+        .run::<RouletteWheelPopulation<_>, BitString>(10, 3, [7]);
+        assert_eq!(unsolved.success_rate(), 0.0);
+        assert_eq!(unsolved.runs[0].generations, 3);
+        assert_eq!(unsolved.mean_evaluations(), None);
+    }
+}
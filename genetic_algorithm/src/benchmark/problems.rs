@@ -0,0 +1,272 @@
+use crate::*;
+use std::f32::consts::{E, PI};
+
+/// Minimisation problems score `1 / (1 + cost)`, so fitness stays positive
+/// and reaches 1 at the optimum.
+fn inverse(cost: f32) -> f32 {
+    1.0 / (1.0 + cost.max(0.0))
+}
+
+/// Counts the set bits.
+pub fn one_max(length: usize) -> Benchmark<BitStringConfig> {
+    Benchmark {
+        name: "one_max",
+        config: BitStringConfig::new(length, |bits| {
+            bits.iter().filter(|&&bit| bit).count() as f32
+        }),
+        optimum: length as f32,
+        target: length as f32,
+    }
+}
+
+/// Concatenated traps of `k` bits: a block scores `k` when all its bits are
+/// set and `k - 1 - ones` otherwise, which leads hill climbers to all zeros.
+pub fn deceptive_trap(blocks: usize, k: usize) -> Benchmark<BitStringConfig> {
+    let optimum = (blocks * k) as f32;
+    Benchmark {
+        name: "deceptive_trap",
+        config: BitStringConfig::new(blocks * k, move |bits| {
+            bits.chunks(k)
+                .map(|block| {
+                    let ones = block.iter().filter(|&&bit| bit).count();
+                    if ones == k {
+                        k as f32
+                    } else {
+                        (k - 1 - ones) as f32
+                    }
+                })
+                .sum()
+        }),
+        optimum,
+        target: optimum,
+    }
+}
+
+fn real(
+    name: &'static str,
+    dimensions: usize,
+    bound: f32,
+    cost: impl Fn(&[f32]) -> f32 + Send + Sync + 'static,
+) -> Benchmark<RealVectorConfig> {
+    Benchmark {
+        name,
+        config: RealVectorConfig::new(vec![(-bound, bound); dimensions], move |x| inverse(cost(x))),
+        optimum: 1.0,
+        target: inverse(0.01),
+    }
+}
+
+/// Minimum 0 at the origin, amid a regular grid of local minima.
+pub fn rastrigin(dimensions: usize) -> Benchmark<RealVectorConfig> {
+    real("rastrigin", dimensions, 5.12, |x| {
+        10.0 * x.len() as f32
+            + x.iter()
+                .map(|x| x * x - 10.0 * (2.0 * PI * x).cos())
+                .sum::<f32>()
+    })
+}
+
+/// Minimum 0 at `(1, ..., 1)`, at the bottom of a narrow curved valley.
+pub fn rosenbrock(dimensions: usize) -> Benchmark<RealVectorConfig> {
+    real("rosenbrock", dimensions, 2.048, |x| {
+        x.windows(2)
+            .map(|w| 100.0 * (w[1] - w[0] * w[0]).powi(2) + (1.0 - w[0]).powi(2))
+            .sum()
+    })
+}
+
+/// Minimum 0 at the origin of an almost flat, rugged landscape.
+pub fn ackley(dimensions: usize) -> Benchmark<RealVectorConfig> {
+    real("ackley", dimensions, 32.768, |x| {
+        let n = x.len().max(1) as f32;
+        let squares = x.iter().map(|x| x * x).sum::<f32>() / n;
+        let cosines = x.iter().map(|x| (2.0 * PI * x).cos()).sum::<f32>() / n;
+        -20.0 * (-0.2 * squares.sqrt()).exp() - cosines.exp() + 20.0 + E
+    })
+}
+
+/// Coordinates of TSPLIB's burma14, as latitude and longitude in degrees
+/// and minutes.
+const BURMA14: [(f32, f32); 14] = [
+    (16.47, 96.10),
+    (16.47, 94.44),
+    (20.09, 92.54),
+    (22.39, 93.37),
+    (25.23, 97.24),
+    (22.00, 96.05),
+    (20.47, 97.02),
+    (17.20, 96.29),
+    (16.30, 97.38),
+    (14.05, 98.12),
+    (16.53, 97.38),
+    (21.52, 95.59),
+    (19.41, 97.13),
+    (20.09, 94.55),
+];
+
+/// Length of the shortest burma14 tour.
+const BURMA14_OPTIMUM: u32 = 3323;
+
+/// TSPLIB's `GEO` distance between burma14 cities, in whole kilometres.
+pub fn burma14_distances() -> Vec<Vec<u32>> {
+    let radians = |x: f32| {
+        let x = x as f64;
+        let degrees = x.trunc();
+        std::f64::consts::PI * (degrees + 5.0 * (x - degrees) / 3.0) / 180.0
+    };
+    let cities: Vec<_> = BURMA14
+        .iter()
+        .map(|&(latitude, longitude)| (radians(latitude), radians(longitude)))
+        .collect();
+
+    cities
+        .iter()
+        .map(|&(lat_a, lon_a)| {
+            cities
+                .iter()
+                .map(|&(lat_b, lon_b)| {
+                    let q1 = (lon_a - lon_b).cos();
+                    let q2 = (lat_a - lat_b).cos();
+                    let q3 = (lat_a + lat_b).cos();
+                    let arc = (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).clamp(-1.0, 1.0);
+                    (6378.388 * arc.acos() + 1.0) as u32
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn tour_length(distances: &[Vec<u32>], tour: &[usize]) -> u32 {
+    tour.iter()
+        .zip(tour.iter().cycle().skip(1))
+        .map(|(&a, &b)| distances[a][b])
+        .sum()
+}
+
+/// Travelling salesman over burma14, scoring the optimal length over the
+/// tour's length.
+pub fn tsp() -> Benchmark<PermutationConfig> {
+    let distances = burma14_distances();
+    Benchmark {
+        name: "tsp",
+        config: PermutationConfig::new(BURMA14.len(), move |tour| {
+            BURMA14_OPTIMUM as f32 / tour_length(&distances, tour) as f32
+        }),
+        optimum: 1.0,
+        target: 1.0,
+    }
+}
+
+/// Weights and profits of the P07 instance from Kreher and Stinson.
+const KNAPSACK_ITEMS: [(u32, u32); 15] = [
+    (70, 135),
+    (73, 139),
+    (77, 149),
+    (80, 150),
+    (82, 156),
+    (87, 163),
+    (90, 173),
+    (94, 184),
+    (98, 192),
+    (106, 201),
+    (110, 210),
+    (113, 214),
+    (115, 221),
+    (118, 229),
+    (120, 240),
+];
+const KNAPSACK_CAPACITY: u32 = 750;
+const KNAPSACK_OPTIMUM: u32 = 1458;
+
+/// 0/1 knapsack; overweight selections score half their profit scaled
+/// down to the capacity, which keeps them well below the optimum.
+pub fn knapsack() -> Benchmark<BitStringConfig> {
+    Benchmark {
+        name: "knapsack",
+        config: BitStringConfig::new(KNAPSACK_ITEMS.len(), |bits| {
+            let (weight, profit) = bits
+                .iter()
+                .zip(KNAPSACK_ITEMS)
+                .filter(|(&bit, _)| bit)
+                .fold((0, 0), |(w, p), (_, (weight, profit))| {
+                    (w + weight, p + profit)
+                });
+            if weight <= KNAPSACK_CAPACITY {
+                profit as f32
+            } else {
+                0.5 * profit as f32 * KNAPSACK_CAPACITY as f32 / weight as f32
+            }
+        }),
+        optimum: KNAPSACK_OPTIMUM as f32,
+        target: KNAPSACK_OPTIMUM as f32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fitness of `genes`, checked not to beat the optimum.
+    fn score<T>(benchmark: &Benchmark<impl Clone>, fitness: &FitnessFn<T>, genes: &[T]) -> f32 {
+        let fitness = fitness(genes);
+        assert!(fitness <= benchmark.optimum);
+        fitness
+    }
+
+    #[test]
+    fn optima() {
+        let b = one_max(10);
+        assert_eq!(score(&b, &b.config.fitness, &[true; 10]), 10.0);
+
+        let b = deceptive_trap(3, 4);
+        assert_eq!(score(&b, &b.config.fitness, &[true; 12]), 12.0);
+        assert_eq!(score(&b, &b.config.fitness, &[false; 12]), 9.0);
+
+        for b in [rastrigin(5), ackley(5)] {
+            assert!(score(&b, &b.config.fitness, &[0.0; 5]) >= b.target);
+        }
+        let b = rosenbrock(5);
+        assert_eq!(score(&b, &b.config.fitness, &[1.0; 5]), 1.0);
+    }
+
+    #[test]
+    fn tsp_optimum() {
+        // Held-Karp over tours starting at city 0.
+        let distances = burma14_distances();
+        let n = distances.len();
+        let mut shortest = vec![vec![u32::MAX; n]; 1 << n];
+        shortest[1][0] = 0;
+        for visited in 1..1usize << n {
+            for last in 0..n {
+                let length = shortest[visited][last];
+                if length == u32::MAX {
+                    continue;
+                }
+                for next in (0..n).filter(|next| visited & (1 << next) == 0) {
+                    let entry = &mut shortest[visited | (1 << next)][next];
+                    *entry = (*entry).min(length + distances[last][next]);
+                }
+            }
+        }
+        let optimum = (1..n)
+            .map(|last| shortest[(1 << n) - 1][last] + distances[last][0])
+            .min();
+        assert_eq!(optimum, Some(BURMA14_OPTIMUM));
+
+        let b = tsp();
+        assert!(score(&b, &b.config.fitness, &(0..n).collect::<Vec<_>>()) < 1.0);
+    }
+
+    #[test]
+    fn knapsack_optimum() {
+        let b = knapsack();
+        let n = KNAPSACK_ITEMS.len();
+        let optimum = (0..1u32 << n)
+            .map(|mask| {
+                let bits: Vec<_> = (0..n).map(|i| mask & (1 << i) != 0).collect();
+                score(&b, &b.config.fitness, &bits)
+            })
+            .fold(0.0, f32::max);
+        assert_eq!(optimum, b.optimum);
+    }
+}
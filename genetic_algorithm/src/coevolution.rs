@@ -0,0 +1,161 @@
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// Which members of the other population every individual is evaluated
+/// against.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Opponents {
+    #[default]
+    All,
+    /// This many opponents drawn at random, with replacement.
+    Sample(usize),
+}
+
+/// Scores two coevolving populations against each other. `encounter` plays
+/// one member of each and returns both of their scores; an individual's
+/// score is its mean over every encounter it took part in.
+pub fn compete<A, B>(
+    a: &[A],
+    b: &[B],
+    opponents: Opponents,
+    mut encounter: impl FnMut(&A, &B) -> (f32, f32),
+    rng: &mut dyn RngCore,
+) -> (Vec<f32>, Vec<f32>) {
+    let mut scores_a = vec![(0.0, 0usize); a.len()];
+    let mut scores_b = vec![(0.0, 0usize); b.len()];
+    let mut play = |i: usize, j: usize| {
+        let (score_a, score_b) = encounter(&a[i], &b[j]);
+        scores_a[i].0 += score_a;
+        scores_a[i].1 += 1;
+        scores_b[j].0 += score_b;
+        scores_b[j].1 += 1;
+    };
+
+    match opponents {
+        Opponents::All => {
+            for i in 0..a.len() {
+                for j in 0..b.len() {
+                    play(i, j);
+                }
+            }
+        }
+        Opponents::Sample(count) if !a.is_empty() && !b.is_empty() => {
+            for i in 0..a.len() {
+                for _ in 0..count {
+                    play(i, rng.gen_range(0..b.len()));
+                }
+            }
+            for j in 0..b.len() {
+                for _ in 0..count {
+                    play(rng.gen_range(0..a.len()), j);
+                }
+            }
+        }
+        Opponents::Sample(_) => {}
+    }
+
+    let mean = |scores: Vec<(f32, usize)>| {
+        scores
+            .into_iter()
+            .map(|(sum, encounters)| sum / encounters.max(1) as f32)
+            .collect()
+    };
+    (mean(scores_a), mean(scores_b))
+}
+
+/// Deals several coevolving populations out to `count` arenas, in which the
+/// members of every population are evaluated against each other together,
+/// e.g. by sharing a world. Arena `k` gets the `k`-th of `count` equal
+/// shares of each population, in the order of `populations`.
+pub fn arenas<'a, I>(populations: &[&'a [I]], count: usize) -> Vec<Vec<&'a [I]>> {
+    let mut arenas = vec![Vec::with_capacity(populations.len()); count];
+    for population in populations {
+        let mut shares = population.chunks(population.len().div_ceil(count).max(1));
+        for arena in &mut arenas {
+            arena.push(shares.next().unwrap_or(&[]));
+        }
+    }
+    arenas
+}
+
+/// `arenas`, with every share mutable.
+pub fn arenas_mut<I>(populations: Vec<&mut [I]>, count: usize) -> Vec<Vec<&mut [I]>> {
+    let mut arenas: Vec<_> = (0..count)
+        .map(|_| Vec::with_capacity(populations.len()))
+        .collect();
+    for population in populations {
+        let share = population.len().div_ceil(count).max(1);
+        let mut shares = population.chunks_mut(share);
+        for arena in &mut arenas {
+            arena.push(shares.next().unwrap_or(&mut []));
+        }
+    }
+    arenas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// A host beats any smaller parasite; the loser scores nothing.
+    fn encounter(host: &f32, parasite: &f32) -> (f32, f32) {
+        if host > parasite {
+            (1.0, 0.0)
+        } else {
+            (0.0, 1.0)
+        }
+    }
+
+    #[test]
+    fn all_against_all() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let (hosts, parasites) = compete(
+            &[0.0, 2.0, 4.0],
+            &[1.0, 3.0],
+            Opponents::All,
+            encounter,
+            &mut rng,
+        );
+
+        assert_eq!(hosts, [0.0, 0.5, 1.0]);
+        assert_eq!(parasites, [1.0 / 3.0, 2.0 / 3.0]);
+    }
+
+    #[test]
+    fn sampled_opponents() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut encounters = 0;
+        let (hosts, parasites) = compete(
+            &[0.0, 2.0, 4.0],
+            &[1.0, 3.0],
+            Opponents::Sample(4),
+            |host, parasite| {
+                encounters += 1;
+                encounter(host, parasite)
+            },
+            &mut rng,
+        );
+
+        assert_eq!(encounters, (3 + 2) * 4);
+        assert_eq!((hosts[0], hosts[2]), (0.0, 1.0));
+        assert!(parasites.iter().all(|score| (0.0..=1.0).contains(score)));
+    }
+
+    #[test]
+    fn arenas_share_every_population() {
+        let prey = [1, 2, 3, 4, 5, 6];
+        let predators = [7, 8];
+        let expected: [[&[i32]; 3]; 2] = [[&[1, 2, 3], &[7], &[]], [&[4, 5, 6], &[8], &[]]];
+
+        assert_eq!(arenas(&[&prey, &predators, &[]], 2), expected);
+
+        let (mut prey, mut predators) = (prey, predators);
+        for mut arena in arenas_mut(vec![&mut prey[..], &mut predators[..]], 2) {
+            arena[0][0] += arena[1][0];
+        }
+        assert_eq!(prey, [8, 2, 3, 12, 5, 6]);
+    }
+}
@@ -0,0 +1,186 @@
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// How constraint violations weigh on fitness; see `ConstraintHandler`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstraintHandling {
+    /// Subtracts `weight` times the total violation.
+    StaticPenalty { weight: f32 },
+    /// A penalty whose weight is multiplied by `factor` after every
+    /// generation whose best individual is infeasible, and divided by it
+    /// after every one whose best is feasible.
+    AdaptivePenalty { weight: f32, factor: f32 },
+    /// Ranks by Deb's rules: feasible individuals beat infeasible ones,
+    /// feasible ones compare by fitness and infeasible ones by violation.
+    /// Selecting in proportion to the ranks makes every feasible individual
+    /// likelier to breed than any infeasible one.
+    #[default]
+    FeasibilityFirst,
+    /// Runarsson and Yao's stochastic bubble sort: neighbours compare by
+    /// fitness when both are feasible or with probability `pf`, and by
+    /// violation otherwise.
+    StochasticRanking { pf: f32 },
+}
+
+/// Deb's feasibility rules, with the better individual ordered last.
+pub fn feasibility_first(a: (f32, f32), b: (f32, f32)) -> Ordering {
+    let ((fitness_a, violation_a), (fitness_b, violation_b)) = (a, b);
+    match (violation_a > 0.0, violation_b > 0.0) {
+        (false, false) => fitness_a.total_cmp(&fitness_b),
+        (true, true) => violation_b.total_cmp(&violation_a),
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+    }
+}
+
+/// Turns fitnesses into scores that account for constraint violations,
+/// keeping the state of adaptive penalties between generations.
+#[derive(Clone, Debug)]
+pub struct ConstraintHandler {
+    handling: ConstraintHandling,
+    weight: f32,
+}
+
+impl ConstraintHandler {
+    pub fn new(handling: ConstraintHandling) -> Self {
+        let weight = match handling {
+            ConstraintHandling::StaticPenalty { weight }
+            | ConstraintHandling::AdaptivePenalty { weight, .. } => weight,
+            _ => 0.0,
+        };
+
+        Self { handling, weight }
+    }
+
+    /// Current penalty weight, zero for the ranking strategies.
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+
+    pub fn set_weight(&mut self, weight: f32) {
+        self.weight = weight;
+    }
+
+    /// Replaces the fitnesses of a generation with scores to select by,
+    /// given the total violation of every individual. The ranking strategies score
+    /// `1` for the worst up to the population size for the best.
+    pub fn apply(&mut self, fitnesses: &mut [f32], violations: &[f32], rng: &mut dyn RngCore) {
+        assert_eq!(fitnesses.len(), violations.len());
+
+        match self.handling {
+            ConstraintHandling::StaticPenalty { .. } => self.penalize(fitnesses, violations),
+            ConstraintHandling::AdaptivePenalty { factor, .. } => {
+                self.penalize(fitnesses, violations);
+
+                let best =
+                    (0..fitnesses.len()).max_by(|&a, &b| fitnesses[a].total_cmp(&fitnesses[b]));
+                if let Some(best) = best {
+                    if violations[best] > 0.0 {
+                        self.weight *= factor;
+                    } else {
+                        self.weight /= factor;
+                    }
+                }
+            }
+            ConstraintHandling::FeasibilityFirst => {
+                let mut order: Vec<usize> = (0..fitnesses.len()).collect();
+                order.sort_by(|&a, &b| {
+                    feasibility_first((fitnesses[a], violations[a]), (fitnesses[b], violations[b]))
+                });
+                rank(fitnesses, &order);
+            }
+            ConstraintHandling::StochasticRanking { pf } => {
+                let mut order: Vec<usize> = (0..fitnesses.len()).collect();
+                for _ in 0..order.len() {
+                    let mut swapped = false;
+                    for i in 1..order.len() {
+                        let (a, b) = (order[i - 1], order[i]);
+                        let by_fitness = (violations[a] <= 0.0 && violations[b] <= 0.0)
+                            || rng.gen_bool(pf as f64);
+                        let better = if by_fitness {
+                            fitnesses[a] > fitnesses[b]
+                        } else {
+                            violations[a] < violations[b]
+                        };
+
+                        if better {
+                            order.swap(i - 1, i);
+                            swapped = true;
+                        }
+                    }
+
+                    if !swapped {
+                        break;
+                    }
+                }
+                rank(fitnesses, &order);
+            }
+        }
+    }
+
+    fn penalize(&self, fitnesses: &mut [f32], violations: &[f32]) {
+        for (fitness, violation) in fitnesses.iter_mut().zip(violations) {
+            *fitness -= self.weight * violation;
+        }
+    }
+}
+
+/// Scores every individual by its position in `order`, worst first.
+fn rank(fitnesses: &mut [f32], order: &[usize]) {
+    for (rank, &i) in order.iter().enumerate() {
+        fitnesses[i] = (rank + 1) as f32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    const FITNESSES: [f32; 4] = [5.0, 9.0, 1.0, 7.0];
+    const VIOLATIONS: [f32; 4] = [0.0, 2.0, 0.0, 0.5];
+
+    fn handled(handler: &mut ConstraintHandler) -> Vec<f32> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut fitnesses = FITNESSES.to_vec();
+        handler.apply(&mut fitnesses, &VIOLATIONS, &mut rng);
+        fitnesses
+    }
+
+    #[test]
+    fn penalties() {
+        let mut handler = ConstraintHandler::new(ConstraintHandling::StaticPenalty { weight: 2.0 });
+        assert_eq!(handled(&mut handler), [5.0, 5.0, 1.0, 6.0]);
+        assert_eq!(handler.weight(), 2.0);
+
+        let mut handler = ConstraintHandler::new(ConstraintHandling::AdaptivePenalty {
+            weight: 1.0,
+            factor: 2.0,
+        });
+        assert_eq!(handled(&mut handler), [5.0, 7.0, 1.0, 6.5]);
+        assert_eq!(handler.weight(), 2.0);
+        assert_eq!(handled(&mut handler), [5.0, 5.0, 1.0, 6.0]);
+        assert_eq!(handler.weight(), 4.0);
+
+        handler.set_weight(10.0);
+        assert_eq!(handled(&mut handler), [5.0, -11.0, 1.0, 2.0]);
+        assert_eq!(handler.weight(), 5.0);
+    }
+
+    #[test]
+    fn rankings() {
+        let mut handler = ConstraintHandler::new(ConstraintHandling::FeasibilityFirst);
+        assert_eq!(handled(&mut handler), [4.0, 1.0, 3.0, 2.0]);
+
+        // Never comparing infeasible individuals by fitness is Deb's order.
+        let mut handler = ConstraintHandler::new(ConstraintHandling::StochasticRanking { pf: 0.0 });
+        assert_eq!(handled(&mut handler), [4.0, 1.0, 3.0, 2.0]);
+
+        // Always comparing by fitness ignores the constraints.
+        let mut handler = ConstraintHandler::new(ConstraintHandling::StochasticRanking { pf: 1.0 });
+        assert_eq!(handled(&mut handler), [2.0, 4.0, 1.0, 3.0]);
+    }
+}
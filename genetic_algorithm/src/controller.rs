@@ -0,0 +1,219 @@
+use crate::*;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StopCondition {
+    /// Generations run by this controller, not counting earlier ones.
+    MaxGenerations(usize),
+    TargetFitness(f32),
+    /// The best fitness seen has not improved for this many generations.
+    Stagnation(usize),
+    WallClock(Duration),
+    /// Genotype diversity fell below this; never holds for individuals
+    /// without `Individual::distance`.
+    DiversityCollapse(f32),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunSummary {
+    pub generations: usize,
+    pub best_fitness: f32,
+    pub elapsed: Duration,
+    pub reason: StopCondition,
+}
+
+/// Called with the generation number within the run and its statistics.
+pub type GenerationCallback<'a, T> = Box<dyn FnMut(usize, &T) + 'a>;
+
+/// Drives a run generation by generation until one of its stop conditions
+/// holds, passing every generation's statistics to its callbacks.
+pub struct RunController<'a, T = PopulationStatistics> {
+    conditions: Vec<StopCondition>,
+    callbacks: Vec<GenerationCallback<'a, T>>,
+    generation: usize,
+    started: Option<Instant>,
+    best_fitness: f32,
+    stagnant: usize,
+}
+
+impl<'a, T> Default for RunController<'a, T> {
+    fn default() -> Self {
+        Self {
+            conditions: vec![],
+            callbacks: vec![],
+            generation: 0,
+            started: None,
+            best_fitness: f32::NEG_INFINITY,
+            stagnant: 0,
+        }
+    }
+}
+
+impl<'a, T> RunController<'a, T>
+where
+    T: AsRef<PopulationStatistics>,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stop_when(mut self, condition: StopCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn on_generation(mut self, callback: impl FnMut(usize, &T) + 'a) -> Self {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn best_fitness(&self) -> f32 {
+        self.best_fitness
+    }
+
+    /// Accounts for one finished generation and returns the first stop
+    /// condition that now holds, if any.
+    pub fn record(&mut self, statistics: &T) -> Option<StopCondition> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let stats = statistics.as_ref();
+
+        for callback in &mut self.callbacks {
+            callback(self.generation, statistics);
+        }
+        self.generation += 1;
+
+        if stats.max_fitness() > self.best_fitness {
+            self.best_fitness = stats.max_fitness();
+            self.stagnant = 0;
+        } else {
+            self.stagnant += 1;
+        }
+
+        self.conditions
+            .iter()
+            .find(|condition| match **condition {
+                StopCondition::MaxGenerations(generations) => self.generation >= generations,
+                StopCondition::TargetFitness(target) => self.best_fitness >= target,
+                StopCondition::Stagnation(generations) => self.stagnant >= generations,
+                StopCondition::WallClock(budget) => started.elapsed() >= budget,
+                StopCondition::DiversityCollapse(threshold) => stats
+                    .diversity()
+                    .is_some_and(|diversity| diversity < threshold),
+            })
+            .cloned()
+    }
+
+    /// Calls `step` for one generation at a time until a stop condition
+    /// holds.
+    pub fn run(&mut self, mut step: impl FnMut() -> T) -> RunSummary {
+        assert!(!self.conditions.is_empty(), "the run would never stop");
+
+        let started = *self.started.get_or_insert_with(Instant::now);
+        loop {
+            if let Some(reason) = self.record(&step()) {
+                return RunSummary {
+                    generations: self.generation,
+                    best_fitness: self.best_fitness,
+                    elapsed: started.elapsed(),
+                    reason,
+                };
+            }
+        }
+    }
+}
+
+impl AsRef<PopulationStatistics> for PopulationStatistics {
+    fn as_ref(&self) -> &PopulationStatistics {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::RngCore;
+
+    struct Value(f32);
+
+    impl Individual<()> for Value {
+        fn random(_params: &(), _rng: &mut dyn RngCore) -> Self {
+            Value(0.0)
+        }
+
+        fn fitness(&self) -> f32 {
+            self.0
+        }
+
+        fn mutate(self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            self
+        }
+
+        fn crossover(&self, _other: &Self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            Value(self.0)
+        }
+
+        fn distance(&self, other: &Self) -> Option<f32> {
+            Some((self.0 - other.0).abs())
+        }
+    }
+
+    /// Statistics of a two-member population `[best - spread, best]`.
+    fn generations(generations: &[(f32, f32)]) -> impl FnMut() -> PopulationStatistics + '_ {
+        let mut generations = generations.iter().cycle();
+        move || {
+            let &(best, spread) = generations.next().unwrap();
+            PopulationStatistics::new(&[Value(best - spread), Value(best)])
+        }
+    }
+
+    #[test]
+    fn stop_conditions() {
+        let run = |condition, history: &[(f32, f32)]| {
+            RunController::new()
+                .stop_when(condition)
+                .stop_when(StopCondition::MaxGenerations(100))
+                .run(generations(history))
+        };
+
+        let summary = run(
+            StopCondition::TargetFitness(3.0),
+            &[(1.0, 1.0), (2.0, 1.0), (3.0, 1.0)],
+        );
+        assert_eq!(summary.reason, StopCondition::TargetFitness(3.0));
+        assert_eq!(summary.generations, 3);
+        assert_eq!(summary.best_fitness, 3.0);
+
+        let summary = run(StopCondition::Stagnation(4), &[(1.0, 1.0), (2.0, 1.0)]);
+        assert_eq!(summary.generations, 6);
+
+        let summary = run(
+            StopCondition::DiversityCollapse(0.5),
+            &[(1.0, 1.0), (1.0, 0.1)],
+        );
+        assert_eq!(summary.generations, 2);
+
+        let summary = run(StopCondition::WallClock(Duration::ZERO), &[(1.0, 1.0)]);
+        assert_eq!(summary.generations, 1);
+
+        let summary = run(StopCondition::TargetFitness(10.0), &[(1.0, 1.0)]);
+        assert_eq!(summary.reason, StopCondition::MaxGenerations(100));
+    }
+
+    #[test]
+    fn callbacks() {
+        let mut seen = vec![];
+
+        RunController::new()
+            .stop_when(StopCondition::MaxGenerations(3))
+            .on_generation(|generation, stats: &PopulationStatistics| {
+                seen.push((generation, stats.max_fitness()))
+            })
+            .run(generations(&[(1.0, 0.0), (2.0, 0.0)]));
+
+        assert_eq!(seen, [(0, 1.0), (1, 2.0), (2, 1.0)]);
+    }
+}
@@ -0,0 +1,124 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+/// Runs independent evaluations, such as separate worlds, either inline or
+/// on a rayon thread pool. Results always come back in task order, and
+/// tasks seeded with `task_rng` don't depend on scheduling, so both modes
+/// give identical results.
+pub struct Evaluator {
+    pool: Option<ThreadPool>,
+}
+
+impl Evaluator {
+    pub fn sequential() -> Self {
+        Self { pool: None }
+    }
+
+    /// `threads == 0` uses rayon's default, one thread per core.
+    pub fn parallel(threads: usize) -> Self {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .expect("failed to build thread pool");
+
+        Self { pool: Some(pool) }
+    }
+
+    pub fn is_parallel(&self) -> bool {
+        self.pool.is_some()
+    }
+
+    pub fn map<T, R, F>(&self, tasks: &[T], f: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(usize, &T) -> R + Sync + Send,
+    {
+        match &self.pool {
+            Some(pool) => pool.install(|| {
+                tasks
+                    .par_iter()
+                    .enumerate()
+                    .map(|(index, task)| f(index, task))
+                    .collect()
+            }),
+            None => tasks
+                .iter()
+                .enumerate()
+                .map(|(index, task)| f(index, task))
+                .collect(),
+        }
+    }
+
+    pub fn map_mut<T, R, F>(&self, tasks: &mut [T], f: F) -> Vec<R>
+    where
+        T: Send,
+        R: Send,
+        F: Fn(usize, &mut T) -> R + Sync + Send,
+    {
+        match &self.pool {
+            Some(pool) => pool.install(|| {
+                tasks
+                    .par_iter_mut()
+                    .enumerate()
+                    .map(|(index, task)| f(index, task))
+                    .collect()
+            }),
+            None => tasks
+                .iter_mut()
+                .enumerate()
+                .map(|(index, task)| f(index, task))
+                .collect(),
+        }
+    }
+}
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Self::sequential()
+    }
+}
+
+/// Generator for task `index` of a batch seeded with `seed`; every task gets
+/// its own ChaCha stream so they can run in any order.
+pub fn task_rng(seed: u64, index: usize) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(index as u64);
+    rng
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn noisy_sum(index: usize, task: &[f32; 8]) -> f32 {
+        let mut rng = task_rng(42, index);
+        task.iter().map(|x| x * rng.gen::<f32>()).sum()
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let tasks: Vec<[f32; 8]> = (0..64).map(|i| [i as f32; 8]).collect();
+
+        let sequential = Evaluator::sequential().map(&tasks, noisy_sum);
+        let parallel = Evaluator::parallel(4).map(&tasks, noisy_sum);
+
+        assert_eq!(sequential, parallel);
+    }
+
+    #[test]
+    fn map_mut_preserves_order() {
+        let mut tasks: Vec<usize> = (0..32).collect();
+
+        let results = Evaluator::parallel(4).map_mut(&mut tasks, |index, task| {
+            *task *= 2;
+            index
+        });
+
+        assert_eq!(results, (0..32).collect::<Vec<_>>());
+        assert_eq!(tasks, (0..32).map(|i| i * 2).collect::<Vec<_>>());
+    }
+}
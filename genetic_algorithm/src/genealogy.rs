@@ -0,0 +1,377 @@
+use crate::*;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Write};
+
+/// Drawn at random, so ids are reproducible under a seeded generator and
+/// need no shared counter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct IndividualId(pub u64);
+
+impl IndividualId {
+    pub fn random(rng: &mut dyn RngCore) -> Self {
+        Self(rng.gen())
+    }
+}
+
+impl fmt::Display for IndividualId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operator {
+    /// Created without parents.
+    Random,
+    Crossover,
+    Mutation,
+    CrossoverMutation,
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Operator::Random => "random",
+            Operator::Crossover => "crossover",
+            Operator::Mutation => "mutation",
+            Operator::CrossoverMutation => "crossover+mutation",
+        })
+    }
+}
+
+/// Identity and parentage an individual carries with it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pedigree {
+    pub id: IndividualId,
+    pub parents: Vec<IndividualId>,
+    pub operator: Operator,
+}
+
+impl Pedigree {
+    pub fn random(rng: &mut dyn RngCore) -> Self {
+        Self {
+            id: IndividualId::random(rng),
+            parents: vec![],
+            operator: Operator::Random,
+        }
+    }
+
+    pub fn crossover(a: &Pedigree, b: &Pedigree, rng: &mut dyn RngCore) -> Self {
+        Self {
+            id: IndividualId::random(rng),
+            parents: vec![a.id, b.id],
+            operator: Operator::Crossover,
+        }
+    }
+
+    pub fn mutation(parent: &Pedigree, rng: &mut dyn RngCore) -> Self {
+        Self {
+            id: IndividualId::random(rng),
+            parents: vec![parent.id],
+            operator: Operator::Mutation,
+        }
+    }
+
+    /// The pedigree of a mutant of this individual: the same birth for a
+    /// crossover offspring that was never evaluated, a new one otherwise.
+    pub fn mutant(&self, evaluated: bool, rng: &mut dyn RngCore) -> Self {
+        if !evaluated && self.operator == Operator::Crossover {
+            self.mutated()
+        } else {
+            Self::mutation(self, rng)
+        }
+    }
+
+    /// The same individual after mutating a fresh crossover offspring in
+    /// place, before it was ever evaluated.
+    pub fn mutated(&self) -> Self {
+        Self {
+            operator: match self.operator {
+                Operator::Crossover => Operator::CrossoverMutation,
+                operator => operator,
+            },
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub id: IndividualId,
+    pub parents: Vec<IndividualId>,
+    pub operator: Operator,
+    /// Generation in which the individual was first recorded.
+    pub generation: usize,
+}
+
+/// Every individual recorded so far, with its parents.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Genealogy {
+    records: BTreeMap<IndividualId, Record>,
+}
+
+impl Genealogy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the members of `population` that carry a pedigree and have
+    /// not been seen before as born in `generation`.
+    pub fn record<I, C>(&mut self, population: &[I], generation: usize)
+    where
+        I: Individual<C>,
+    {
+        for pedigree in population.iter().filter_map(I::pedigree) {
+            self.records.entry(pedigree.id).or_insert_with(|| Record {
+                id: pedigree.id,
+                parents: pedigree.parents.clone(),
+                operator: pedigree.operator,
+                generation,
+            });
+        }
+    }
+
+    pub fn get(&self, id: IndividualId) -> Option<&Record> {
+        self.records.get(&id)
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = &Record> {
+        self.records.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Recorded ancestors of `id`, not including `id` itself.
+    pub fn ancestors(&self, id: IndividualId) -> BTreeSet<IndividualId> {
+        let mut ancestors = BTreeSet::new();
+        let mut pending = vec![id];
+
+        while let Some(id) = pending.pop() {
+            for parent in self.records.get(&id).into_iter().flat_map(|r| &r.parents) {
+                if self.records.contains_key(parent) && ancestors.insert(*parent) {
+                    pending.push(*parent);
+                }
+            }
+        }
+
+        ancestors
+    }
+
+    /// The latest-born individual every one of `ids` descends from, or is.
+    pub fn most_recent_common_ancestor(&self, ids: &[IndividualId]) -> Option<&Record> {
+        let mut common: Option<BTreeSet<IndividualId>> = None;
+
+        for &id in ids {
+            let mut lineage = self.ancestors(id);
+            if self.records.contains_key(&id) {
+                lineage.insert(id);
+            }
+            common = Some(match common {
+                Some(common) => &common & &lineage,
+                None => lineage,
+            });
+        }
+
+        common?
+            .iter()
+            .map(|id| &self.records[id])
+            .max_by_key(|record| (record.generation, record.id))
+    }
+
+    /// Generations between `generation` and the most recent common ancestor
+    /// of `ids`; `None` if their lineages never meet.
+    pub fn coalescence_time(&self, ids: &[IndividualId], generation: usize) -> Option<usize> {
+        self.most_recent_common_ancestor(ids)
+            .map(|ancestor| generation.saturating_sub(ancestor.generation))
+    }
+
+    /// Fraction of the individuals born in `generation` that have a
+    /// descendant among, or are one of, `living`.
+    pub fn lineage_survival(&self, generation: usize, living: &[IndividualId]) -> Option<f32> {
+        let mut survivors: BTreeSet<IndividualId> = living.iter().copied().collect();
+        for &id in living {
+            survivors.extend(self.ancestors(id));
+        }
+
+        let born: Vec<_> = self
+            .records
+            .values()
+            .filter(|record| record.generation == generation)
+            .collect();
+        if born.is_empty() {
+            return None;
+        }
+
+        let surviving = born
+            .iter()
+            .filter(|record| survivors.contains(&record.id))
+            .count();
+
+        Some(surviving as f32 / born.len() as f32)
+    }
+
+    /// Forgets everyone that is neither in `living` nor one of their
+    /// ancestors.
+    pub fn prune(&mut self, living: &[IndividualId]) {
+        let mut keep: BTreeSet<IndividualId> = living.iter().copied().collect();
+        for &id in living {
+            keep.extend(self.ancestors(id));
+        }
+
+        self.records.retain(|id, _| keep.contains(id));
+    }
+
+    /// Graphviz digraph with an edge from every parent to its child.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph genealogy {\n");
+
+        for record in self.records.values() {
+            writeln!(
+                dot,
+                "  \"{}\" [label=\"{}\\ngen {}\\n{}\"];",
+                record.id, record.id, record.generation, record.operator
+            )
+            .unwrap();
+        }
+        for (parent, child) in self.edges() {
+            writeln!(dot, "  \"{}\" -> \"{}\";", parent, child).unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut graphml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"generation\" for=\"node\" attr.name=\"generation\" attr.type=\"int\"/>\n",
+            "  <key id=\"operator\" for=\"node\" attr.name=\"operator\" attr.type=\"string\"/>\n",
+            "  <graph id=\"genealogy\" edgedefault=\"directed\">\n",
+        ));
+
+        for record in self.records.values() {
+            writeln!(
+                graphml,
+                concat!(
+                    "    <node id=\"{}\">",
+                    "<data key=\"generation\">{}</data>",
+                    "<data key=\"operator\">{}</data></node>"
+                ),
+                record.id, record.generation, record.operator
+            )
+            .unwrap();
+        }
+        for (parent, child) in self.edges() {
+            writeln!(
+                graphml,
+                "    <edge source=\"{}\" target=\"{}\"/>",
+                parent, child
+            )
+            .unwrap();
+        }
+
+        graphml.push_str("  </graph>\n</graphml>\n");
+        graphml
+    }
+
+    /// Parent to child edges between recorded individuals.
+    fn edges(&self) -> impl Iterator<Item = (IndividualId, IndividualId)> + '_ {
+        self.records.values().flat_map(move |record| {
+            record
+                .parents
+                .iter()
+                .filter(|parent| self.records.contains_key(parent))
+                .map(move |parent| (*parent, record.id))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(id: u64) -> IndividualId {
+        IndividualId(id)
+    }
+
+    /// 1 and 2 are founders; 3 = 1 x 2, 4 = mutant of 1, then
+    /// 5 = 3 x 4 and 6 = mutant of 3.
+    fn genealogy() -> Genealogy {
+        let mut genealogy = Genealogy::new();
+
+        for (child, parents, generation) in [
+            (1, vec![], 0),
+            (2, vec![], 0),
+            (3, vec![1, 2], 1),
+            (4, vec![1], 1),
+            (5, vec![3, 4], 2),
+            (6, vec![3], 2),
+        ] {
+            genealogy.records.insert(
+                id(child),
+                Record {
+                    id: id(child),
+                    parents: parents.into_iter().map(id).collect(),
+                    operator: Operator::Crossover,
+                    generation,
+                },
+            );
+        }
+
+        genealogy
+    }
+
+    #[test]
+    fn ancestry() {
+        let genealogy = genealogy();
+
+        assert_eq!(
+            genealogy.ancestors(id(5)),
+            [1, 2, 3, 4].map(id).into_iter().collect()
+        );
+        assert_eq!(
+            genealogy
+                .most_recent_common_ancestor(&[id(5), id(6)])
+                .unwrap()
+                .id,
+            id(3)
+        );
+        assert_eq!(genealogy.coalescence_time(&[id(5), id(6)], 2), Some(1));
+        assert_eq!(genealogy.coalescence_time(&[id(2), id(4)], 1), None);
+    }
+
+    #[test]
+    fn lineage_survival() {
+        let mut genealogy = genealogy();
+
+        assert_eq!(genealogy.lineage_survival(1, &[id(6)]), Some(0.5));
+        assert_eq!(genealogy.lineage_survival(0, &[id(6)]), Some(1.0));
+        assert_eq!(genealogy.lineage_survival(3, &[id(6)]), None);
+
+        genealogy.prune(&[id(6)]);
+        assert_eq!(genealogy.len(), 4);
+    }
+
+    #[test]
+    fn exports() {
+        let genealogy = genealogy();
+
+        let dot = genealogy.to_dot();
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\"", id(3), id(6))));
+        assert_eq!(dot.matches("->").count(), 6);
+
+        let graphml = genealogy.to_graphml();
+        assert_eq!(graphml.matches("<node ").count(), 6);
+        assert_eq!(graphml.matches("<edge ").count(), 6);
+    }
+}
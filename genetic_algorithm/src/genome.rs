@@ -0,0 +1,102 @@
+mod bit_string;
+mod integer_vector;
+mod permutation;
+mod real_vector;
+
+use crate::Pedigree;
+use rand::RngCore;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+pub use bit_string::*;
+pub use integer_vector::*;
+pub use permutation::*;
+pub use real_vector::*;
+
+/// Fitness function shared by every genome of a population.
+pub type FitnessFn<T> = Arc<dyn Fn(&[T]) -> f32 + Send + Sync>;
+
+/// A fixed-length genome, evaluated lazily and at most once, so offspring
+/// that are mutated straight after crossover cost a single evaluation.
+#[derive(Clone)]
+pub struct Genome<T> {
+    genes: Vec<T>,
+    evaluate: FitnessFn<T>,
+    fitness: OnceLock<f32>,
+    pedigree: Option<Pedigree>,
+}
+
+impl<T> Genome<T> {
+    pub fn new(genes: Vec<T>, evaluate: FitnessFn<T>) -> Self {
+        Self {
+            genes,
+            evaluate,
+            fitness: OnceLock::new(),
+            pedigree: None,
+        }
+    }
+
+    /// A genome without parents, with an identity of its own.
+    pub(crate) fn founder(genes: Vec<T>, evaluate: FitnessFn<T>, rng: &mut dyn RngCore) -> Self {
+        Self {
+            pedigree: Some(Pedigree::random(rng)),
+            ..Self::new(genes, evaluate)
+        }
+    }
+
+    pub fn genes(&self) -> &[T] {
+        &self.genes
+    }
+
+    pub fn into_genes(self) -> Vec<T> {
+        self.genes
+    }
+
+    pub(crate) fn evaluated(&self) -> f32 {
+        *self.fitness.get_or_init(|| (self.evaluate)(&self.genes))
+    }
+
+    /// A genome of the same kind with other genes, and no identity until
+    /// `breed` gives it one.
+    pub(crate) fn with_genes(&self, genes: Vec<T>) -> Self {
+        Self::new(genes, Arc::clone(&self.evaluate))
+    }
+
+    /// A mutant of this genome with `genes`.
+    pub(crate) fn mutant(&self, genes: Vec<T>, rng: &mut dyn RngCore) -> Self {
+        let evaluated = self.fitness.get().is_some();
+        Self {
+            pedigree: self
+                .pedigree
+                .as_ref()
+                .map(|pedigree| pedigree.mutant(evaluated, rng)),
+            ..self.with_genes(genes)
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Genome<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Genome")
+            .field("genes", &self.genes)
+            .field("fitness", &self.fitness.get())
+            .field("pedigree", &self.pedigree)
+            .finish()
+    }
+}
+
+/// Uniform crossover: every gene from either parent with equal odds.
+pub(crate) fn uniform_crossover<T: Clone>(a: &[T], b: &[T], rng: &mut dyn rand::RngCore) -> Vec<T> {
+    use rand::Rng;
+
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| {
+            if rng.gen_bool(0.5) {
+                x.clone()
+            } else {
+                y.clone()
+            }
+        })
+        .collect()
+}
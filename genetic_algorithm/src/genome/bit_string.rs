@@ -0,0 +1,67 @@
+use crate::genome::uniform_crossover;
+use crate::*;
+use rand::{Rng, RngCore};
+use std::sync::Arc;
+
+pub type BitString = Genome<bool>;
+
+#[derive(Clone)]
+pub struct BitStringConfig {
+    pub length: usize,
+    /// Chance of flipping each bit.
+    pub mutation_rate: f32,
+    pub fitness: FitnessFn<bool>,
+}
+
+impl BitStringConfig {
+    pub fn new(length: usize, fitness: impl Fn(&[bool]) -> f32 + Send + Sync + 'static) -> Self {
+        Self {
+            length,
+            mutation_rate: 1.0 / length.max(1) as f32,
+            fitness: Arc::new(fitness),
+        }
+    }
+}
+
+impl Individual<BitStringConfig> for BitString {
+    fn random(params: &BitStringConfig, rng: &mut dyn RngCore) -> Self {
+        let genes = (0..params.length).map(|_| rng.gen()).collect();
+        Genome::founder(genes, Arc::clone(&params.fitness), rng)
+    }
+
+    fn fitness(&self) -> f32 {
+        self.evaluated()
+    }
+
+    fn mutate(self, params: &BitStringConfig, rng: &mut dyn RngCore) -> Self {
+        let genes = self
+            .genes()
+            .iter()
+            .map(|&bit| bit ^ rng.gen_bool(params.mutation_rate as f64))
+            .collect();
+        self.mutant(genes, rng)
+    }
+
+    fn crossover(&self, other: &Self, _params: &BitStringConfig, rng: &mut dyn RngCore) -> Self {
+        self.with_genes(uniform_crossover(self.genes(), other.genes(), rng))
+    }
+
+    /// Hamming distance.
+    fn distance(&self, other: &Self) -> Option<f32> {
+        let differing = self
+            .genes()
+            .iter()
+            .zip(other.genes())
+            .filter(|(a, b)| a != b)
+            .count();
+        Some(differing as f32)
+    }
+
+    fn pedigree(&self) -> Option<&Pedigree> {
+        self.pedigree.as_ref()
+    }
+
+    fn set_pedigree(&mut self, pedigree: Pedigree) {
+        self.pedigree = Some(pedigree);
+    }
+}
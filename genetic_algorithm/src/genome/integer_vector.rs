@@ -0,0 +1,89 @@
+use crate::genome::uniform_crossover;
+use crate::*;
+use rand::{Rng, RngCore};
+use std::sync::Arc;
+
+pub type IntegerVector = Genome<i32>;
+
+#[derive(Clone)]
+pub struct IntegerVectorConfig {
+    /// Inclusive `(min, max)` of every gene.
+    pub bounds: Vec<(i32, i32)>,
+    /// Chance of resetting each gene to a random value in its bounds.
+    pub mutation_rate: f32,
+    pub fitness: FitnessFn<i32>,
+}
+
+impl IntegerVectorConfig {
+    pub fn new(
+        bounds: Vec<(i32, i32)>,
+        fitness: impl Fn(&[i32]) -> f32 + Send + Sync + 'static,
+    ) -> Self {
+        assert!(bounds.iter().all(|(min, max)| min <= max));
+
+        Self {
+            mutation_rate: 1.0 / bounds.len().max(1) as f32,
+            bounds,
+            fitness: Arc::new(fitness),
+        }
+    }
+}
+
+impl Individual<IntegerVectorConfig> for IntegerVector {
+    fn random(params: &IntegerVectorConfig, rng: &mut dyn RngCore) -> Self {
+        let genes = params
+            .bounds
+            .iter()
+            .map(|&(min, max)| rng.gen_range(min..=max))
+            .collect();
+        Genome::founder(genes, Arc::clone(&params.fitness), rng)
+    }
+
+    fn fitness(&self) -> f32 {
+        self.evaluated()
+    }
+
+    fn mutate(self, params: &IntegerVectorConfig, rng: &mut dyn RngCore) -> Self {
+        let genes = self
+            .genes()
+            .iter()
+            .zip(&params.bounds)
+            .map(|(&x, &(min, max))| {
+                if rng.gen_bool(params.mutation_rate as f64) {
+                    rng.gen_range(min..=max)
+                } else {
+                    x
+                }
+            })
+            .collect();
+        self.mutant(genes, rng)
+    }
+
+    fn crossover(
+        &self,
+        other: &Self,
+        _params: &IntegerVectorConfig,
+        rng: &mut dyn RngCore,
+    ) -> Self {
+        self.with_genes(uniform_crossover(self.genes(), other.genes(), rng))
+    }
+
+    /// Manhattan distance.
+    fn distance(&self, other: &Self) -> Option<f32> {
+        let distance: i64 = self
+            .genes()
+            .iter()
+            .zip(other.genes())
+            .map(|(&a, &b)| (a as i64 - b as i64).abs())
+            .sum();
+        Some(distance as f32)
+    }
+
+    fn pedigree(&self) -> Option<&Pedigree> {
+        self.pedigree.as_ref()
+    }
+
+    fn set_pedigree(&mut self, pedigree: Pedigree) {
+        self.pedigree = Some(pedigree);
+    }
+}
@@ -0,0 +1,291 @@
+use crate::*;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// An ordering of `0..length`.
+pub type Permutation = Genome<usize>;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermutationCrossover {
+    #[default]
+    Order,
+    PartiallyMapped,
+    Cycle,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermutationMutation {
+    #[default]
+    Swap,
+    Inversion,
+}
+
+#[derive(Clone)]
+pub struct PermutationConfig {
+    pub length: usize,
+    pub crossover: PermutationCrossover,
+    pub mutation: PermutationMutation,
+    /// Chance of mutating each offspring once.
+    pub mutation_rate: f32,
+    pub fitness: FitnessFn<usize>,
+}
+
+impl PermutationConfig {
+    pub fn new(length: usize, fitness: impl Fn(&[usize]) -> f32 + Send + Sync + 'static) -> Self {
+        Self {
+            length,
+            crossover: PermutationCrossover::default(),
+            mutation: PermutationMutation::default(),
+            mutation_rate: 0.5,
+            fitness: Arc::new(fitness),
+        }
+    }
+
+    pub fn with_crossover(mut self, crossover: PermutationCrossover) -> Self {
+        self.crossover = crossover;
+        self
+    }
+
+    pub fn with_mutation(mut self, mutation: PermutationMutation) -> Self {
+        self.mutation = mutation;
+        self
+    }
+}
+
+impl Individual<PermutationConfig> for Permutation {
+    fn random(params: &PermutationConfig, rng: &mut dyn RngCore) -> Self {
+        let mut genes: Vec<_> = (0..params.length).collect();
+        genes.shuffle(rng);
+        Genome::founder(genes, Arc::clone(&params.fitness), rng)
+    }
+
+    fn fitness(&self) -> f32 {
+        self.evaluated()
+    }
+
+    fn mutate(self, params: &PermutationConfig, rng: &mut dyn RngCore) -> Self {
+        if !rng.gen_bool(params.mutation_rate as f64) {
+            return self;
+        }
+
+        let mut genes = self.genes().to_vec();
+        match params.mutation {
+            PermutationMutation::Swap => swap_mutation(&mut genes, rng),
+            PermutationMutation::Inversion => inversion_mutation(&mut genes, rng),
+        }
+        self.mutant(genes, rng)
+    }
+
+    fn crossover(&self, other: &Self, params: &PermutationConfig, rng: &mut dyn RngCore) -> Self {
+        let (a, b) = (self.genes(), other.genes());
+        let genes = match params.crossover {
+            PermutationCrossover::Order => order_crossover(a, b, rng),
+            PermutationCrossover::PartiallyMapped => partially_mapped_crossover(a, b, rng),
+            PermutationCrossover::Cycle => cycle_crossover(a, b),
+        };
+        self.with_genes(genes)
+    }
+
+    /// Number of positions holding different elements.
+    fn distance(&self, other: &Self) -> Option<f32> {
+        let differing = self
+            .genes()
+            .iter()
+            .zip(other.genes())
+            .filter(|(a, b)| a != b)
+            .count();
+        Some(differing as f32)
+    }
+
+    fn pedigree(&self) -> Option<&Pedigree> {
+        self.pedigree.as_ref()
+    }
+
+    fn set_pedigree(&mut self, pedigree: Pedigree) {
+        self.pedigree = Some(pedigree);
+    }
+}
+
+/// Random `lo..hi` with `lo < hi`, unless `length < 2`.
+fn segment(length: usize, rng: &mut dyn RngCore) -> (usize, usize) {
+    if length < 2 {
+        return (0, length);
+    }
+
+    let lo = rng.gen_range(0..length - 1);
+    let hi = rng.gen_range(lo + 1..=length);
+    (lo, hi)
+}
+
+/// Position of every element in `permutation`.
+fn positions(permutation: &[usize]) -> Vec<usize> {
+    let mut positions = vec![0; permutation.len()];
+    for (i, &element) in permutation.iter().enumerate() {
+        positions[element] = i;
+    }
+    positions
+}
+
+/// Keeps a random segment of `a` and fills the rest with the remaining
+/// elements in the order they follow that segment in `b`.
+pub fn order_crossover(a: &[usize], b: &[usize], rng: &mut dyn RngCore) -> Vec<usize> {
+    let (lo, hi) = segment(a.len(), rng);
+    order_crossover_at(a, b, lo, hi)
+}
+
+fn order_crossover_at(a: &[usize], b: &[usize], lo: usize, hi: usize) -> Vec<usize> {
+    let n = a.len();
+    let mut child = vec![usize::MAX; n];
+    let mut used = vec![false; n];
+    for i in lo..hi {
+        child[i] = a[i];
+        used[a[i]] = true;
+    }
+
+    let mut position = hi % n.max(1);
+    for &element in b[hi..].iter().chain(&b[..hi]) {
+        if !used[element] {
+            child[position] = element;
+            position = (position + 1) % n;
+        }
+    }
+    child
+}
+
+/// Keeps a random segment of `a` and places the elements of `b`'s segment
+/// through the mapping between both segments.
+pub fn partially_mapped_crossover(a: &[usize], b: &[usize], rng: &mut dyn RngCore) -> Vec<usize> {
+    let (lo, hi) = segment(a.len(), rng);
+    partially_mapped_crossover_at(a, b, lo, hi)
+}
+
+fn partially_mapped_crossover_at(a: &[usize], b: &[usize], lo: usize, hi: usize) -> Vec<usize> {
+    let in_b = positions(b);
+    let mut child = vec![usize::MAX; a.len()];
+    let mut used = vec![false; a.len()];
+    for i in lo..hi {
+        child[i] = a[i];
+        used[a[i]] = true;
+    }
+
+    for i in lo..hi {
+        if used[b[i]] {
+            continue;
+        }
+
+        let mut position = i;
+        while (lo..hi).contains(&position) {
+            position = in_b[a[position]];
+        }
+        child[position] = b[i];
+        used[b[i]] = true;
+    }
+
+    for (gene, &element) in child.iter_mut().zip(b) {
+        if *gene == usize::MAX {
+            *gene = element;
+        }
+    }
+    child
+}
+
+/// Takes alternate cycles of positions from `a` and `b`, so every element
+/// keeps the position it has in one of the parents.
+pub fn cycle_crossover(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let in_a = positions(a);
+    let mut child = vec![usize::MAX; a.len()];
+    let mut from_a = true;
+
+    for start in 0..a.len() {
+        if child[start] != usize::MAX {
+            continue;
+        }
+
+        let mut position = start;
+        loop {
+            child[position] = if from_a { a[position] } else { b[position] };
+            position = in_a[b[position]];
+            if position == start {
+                break;
+            }
+        }
+        from_a = !from_a;
+    }
+    child
+}
+
+/// Swaps two random elements.
+pub fn swap_mutation(permutation: &mut [usize], rng: &mut dyn RngCore) {
+    if permutation.len() < 2 {
+        return;
+    }
+
+    let i = rng.gen_range(0..permutation.len());
+    let j = rng.gen_range(0..permutation.len());
+    permutation.swap(i, j);
+}
+
+/// Reverses a random segment.
+pub fn inversion_mutation(permutation: &mut [usize], rng: &mut dyn RngCore) {
+    let (lo, hi) = segment(permutation.len(), rng);
+    permutation[lo..hi].reverse();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    const A: [usize; 9] = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+    const B: [usize; 9] = [8, 2, 6, 7, 1, 5, 4, 0, 3];
+
+    fn is_permutation(genes: &[usize]) -> bool {
+        let mut sorted = genes.to_vec();
+        sorted.sort();
+        sorted.iter().copied().eq(0..genes.len())
+    }
+
+    #[test]
+    fn crossovers() {
+        assert_eq!(
+            order_crossover_at(&A, &B, 3, 7),
+            [2, 7, 1, 3, 4, 5, 6, 0, 8]
+        );
+        assert_eq!(
+            partially_mapped_crossover_at(&A, &B, 3, 7),
+            [8, 2, 1, 3, 4, 5, 6, 0, 7]
+        );
+        assert_eq!(cycle_crossover(&A, &B), [0, 2, 6, 3, 1, 5, 4, 7, 8]);
+    }
+
+    #[test]
+    fn operators_keep_permutations() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let config = PermutationConfig::new(20, |genes| genes[0] as f32);
+
+        for crossover in [
+            PermutationCrossover::Order,
+            PermutationCrossover::PartiallyMapped,
+            PermutationCrossover::Cycle,
+        ] {
+            for mutation in [PermutationMutation::Swap, PermutationMutation::Inversion] {
+                let config = config
+                    .clone()
+                    .with_crossover(crossover)
+                    .with_mutation(mutation);
+
+                for _ in 0..50 {
+                    let a = Permutation::random(&config, &mut rng);
+                    let b = Permutation::random(&config, &mut rng);
+                    let child = a.crossover(&b, &config, &mut rng).mutate(&config, &mut rng);
+                    assert!(is_permutation(child.genes()));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::genome::uniform_crossover;
+use crate::optimizer::gaussian;
+use crate::*;
+use rand::{Rng, RngCore};
+use std::sync::Arc;
+
+pub type RealVector = Genome<f32>;
+
+#[derive(Clone)]
+pub struct RealVectorConfig {
+    /// Inclusive `(min, max)` of every gene.
+    pub bounds: Vec<(f32, f32)>,
+    /// Chance of perturbing each gene.
+    pub mutation_rate: f32,
+    /// Standard deviation of the Gaussian perturbation, as a fraction of
+    /// the gene's range.
+    pub mutation_sigma: f32,
+    pub fitness: FitnessFn<f32>,
+}
+
+impl RealVectorConfig {
+    pub fn new(
+        bounds: Vec<(f32, f32)>,
+        fitness: impl Fn(&[f32]) -> f32 + Send + Sync + 'static,
+    ) -> Self {
+        assert!(bounds.iter().all(|(min, max)| min <= max));
+
+        Self {
+            mutation_rate: 1.0 / bounds.len().max(1) as f32,
+            mutation_sigma: 0.1,
+            bounds,
+            fitness: Arc::new(fitness),
+        }
+    }
+}
+
+impl Individual<RealVectorConfig> for RealVector {
+    fn random(params: &RealVectorConfig, rng: &mut dyn RngCore) -> Self {
+        let genes = params
+            .bounds
+            .iter()
+            .map(|&(min, max)| rng.gen_range(min..=max))
+            .collect();
+        Genome::founder(genes, Arc::clone(&params.fitness), rng)
+    }
+
+    fn fitness(&self) -> f32 {
+        self.evaluated()
+    }
+
+    fn mutate(self, params: &RealVectorConfig, rng: &mut dyn RngCore) -> Self {
+        let genes = self
+            .genes()
+            .iter()
+            .zip(&params.bounds)
+            .map(|(&x, &(min, max))| {
+                if rng.gen_bool(params.mutation_rate as f64) {
+                    (x + gaussian(rng) * params.mutation_sigma * (max - min)).clamp(min, max)
+                } else {
+                    x
+                }
+            })
+            .collect();
+        self.mutant(genes, rng)
+    }
+
+    fn crossover(&self, other: &Self, _params: &RealVectorConfig, rng: &mut dyn RngCore) -> Self {
+        self.with_genes(uniform_crossover(self.genes(), other.genes(), rng))
+    }
+
+    /// Euclidean distance.
+    fn distance(&self, other: &Self) -> Option<f32> {
+        let squared: f32 = self
+            .genes()
+            .iter()
+            .zip(other.genes())
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum();
+        Some(squared.sqrt())
+    }
+
+    fn pedigree(&self) -> Option<&Pedigree> {
+        self.pedigree.as_ref()
+    }
+
+    fn set_pedigree(&mut self, pedigree: Pedigree) {
+        self.pedigree = Some(pedigree);
+    }
+}
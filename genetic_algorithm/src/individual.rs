@@ -1,8 +1,45 @@
+use crate::Pedigree;
 use rand::RngCore;
 
 pub trait Individual<C> {
     fn random(params: &C, rng: &mut dyn RngCore) -> Self;
     fn fitness(&self) -> f32;
+
+    /// What selection compares; the raw `fitness` unless it was rescaled
+    /// for selection only, which leaves statistics on the raw values.
+    fn score(&self) -> f32 {
+        self.fitness()
+    }
     fn mutate(self, params: &C, rng: &mut dyn RngCore) -> Self;
     fn crossover(&self, other: &Self, params: &C, rng: &mut dyn RngCore) -> Self;
+
+    /// Genotype distance to `other`, used to measure population diversity;
+    /// `None` when the genotype has no meaningful metric.
+    fn distance(&self, _other: &Self) -> Option<f32> {
+        None
+    }
+
+    /// Identity and parentage, for individuals whose lineage is tracked.
+    fn pedigree(&self) -> Option<&Pedigree> {
+        None
+    }
+
+    /// Takes the identity `breed` gives every crossover offspring of
+    /// parents that carry a pedigree.
+    fn set_pedigree(&mut self, _pedigree: Pedigree) {}
+}
+
+pub trait MultiObjectiveIndividual<C>: Individual<C> {
+    /// Objectives to be maximized; every individual must return the same
+    /// number of them.
+    fn objectives(&self) -> Vec<f32>;
+}
+
+pub trait ConstrainedIndividual<C>: Individual<C> {
+    /// How far each constraint is violated, zero for those satisfied.
+    fn violations(&self) -> Vec<f32>;
+
+    fn total_violation(&self) -> f32 {
+        self.violations().iter().map(|v| v.max(0.0)).sum()
+    }
 }
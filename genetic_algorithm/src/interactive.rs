@@ -0,0 +1,138 @@
+use crate::observer::breed;
+use crate::population::RouletteWheel;
+use crate::*;
+use rand::RngCore;
+
+/// Choices made by a person about a population, standing in for numeric
+/// fitness. Individuals are indices into the population.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Preferences {
+    favourites: Vec<usize>,
+    comparisons: Vec<(usize, usize)>,
+}
+
+impl Preferences {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks `index` as a favourite, or unmarks it if it already is one.
+    /// Returns whether it is a favourite now.
+    pub fn toggle_favourite(&mut self, index: usize) -> bool {
+        match self.favourites.iter().position(|&i| i == index) {
+            Some(position) => {
+                self.favourites.remove(position);
+                false
+            }
+            None => {
+                self.favourites.push(index);
+                true
+            }
+        }
+    }
+
+    pub fn is_favourite(&self, index: usize) -> bool {
+        self.favourites.contains(&index)
+    }
+
+    pub fn favourites(&self) -> &[usize] {
+        &self.favourites
+    }
+
+    /// Records that `winner` was preferred over `loser`.
+    pub fn prefer(&mut self, winner: usize, loser: usize) {
+        self.comparisons.push((winner, loser));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.favourites.is_empty() && self.comparisons.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.favourites.clear();
+        self.comparisons.clear();
+    }
+
+    /// A point for being a favourite and one for every comparison won;
+    /// everyone scores the same when nothing was chosen at all.
+    pub fn scores(&self, size: usize) -> Vec<f32> {
+        if self.is_empty() {
+            return vec![1.0; size];
+        }
+
+        let mut scores = vec![0.0; size];
+        for &favourite in &self.favourites {
+            scores[favourite] += 1.0;
+        }
+        for &(winner, _) in &self.comparisons {
+            scores[winner] += 1.0;
+        }
+        scores
+    }
+}
+
+/// Breeds a whole new population from the individuals `preferences` favour,
+/// with parents drawn in proportion to their scores. Individuals scoring
+/// nothing are only drawn when nobody scores anything.
+pub fn breed_preferred<I, C>(
+    population: &[I],
+    preferences: &Preferences,
+    params: &C,
+    rng: &mut dyn RngCore,
+) -> Vec<I>
+where
+    I: Individual<C>,
+{
+    let scores = preferences.scores(population.len());
+    let mut parents: Vec<usize> = (0..population.len()).filter(|&i| scores[i] > 0.0).collect();
+    if parents.is_empty() {
+        parents = (0..population.len()).collect();
+    }
+
+    let wheel = RouletteWheel::from_fitness(parents.iter().map(|&i| scores[i]));
+    let mut children = vec![];
+    breed(
+        population,
+        population.len(),
+        params,
+        rng,
+        &mut Observers::new(),
+        |rng| parents[wheel.spin(rng)],
+        &mut children,
+    );
+    children
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::sync::Arc;
+
+    #[test]
+    fn breeds_from_favourites() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut preferences = Preferences::new();
+        assert!(preferences.toggle_favourite(2));
+        assert!(preferences.toggle_favourite(0));
+        assert!(!preferences.toggle_favourite(0));
+        preferences.prefer(4, 3);
+        assert_eq!(preferences.scores(5), [0.0, 0.0, 1.0, 0.0, 1.0]);
+
+        // Only the all-ones strings were chosen, and without mutation their
+        // children are all ones as well.
+        let config = BitStringConfig {
+            mutation_rate: 0.0,
+            ..BitStringConfig::new(8, |_| 0.0)
+        };
+        let population: Vec<_> = [false, false, true, false, true]
+            .iter()
+            .map(|&bit| Genome::new(vec![bit; 8], Arc::clone(&config.fitness)))
+            .collect();
+
+        let children = breed_preferred(&population, &preferences, &config, &mut rng);
+        assert_eq!(children.len(), 5);
+        assert!(children.iter().all(|child| child.genes() == [true; 8]));
+    }
+}
@@ -1,22 +1,56 @@
+mod aggregation;
+mod benchmark;
+mod coevolution;
+mod constraint;
+mod controller;
+mod evaluator;
+mod genealogy;
+mod genome;
 mod individual;
+mod interactive;
+mod map_elites;
+mod mutation;
+mod novelty;
+mod observer;
+mod optimizer;
+mod pareto;
 mod population;
+mod scaling;
 mod stats;
 
+pub use self::aggregation::*;
+pub use self::benchmark::*;
+pub use self::coevolution::*;
+pub use self::constraint::*;
+pub use self::controller::*;
+pub use self::evaluator::*;
+pub use self::genealogy::*;
+pub use self::genome::*;
 pub use self::individual::*;
+pub use self::interactive::*;
+pub use self::map_elites::*;
+pub use self::mutation::*;
+pub use self::novelty::*;
+pub use self::observer::*;
+pub use self::optimizer::*;
+pub use self::pareto::*;
 pub use self::population::*;
+pub use self::scaling::*;
 pub use self::stats::*;
 
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+    fn solves_one_max() {
+        let benchmark = Benchmark {
+            target: 28.0,
+            ..one_max(32)
+        };
+        let report = benchmark.run::<RouletteWheelPopulation<_>, BitString>(100, 50, 0..8);
+
+        assert!(report.success_rate() >= 0.75, "{report}");
+        assert!(report.mean_best_fitness() >= 28.0, "{report}");
     }
 }
@@ -0,0 +1,249 @@
+use crate::*;
+use rand::seq::IteratorRandom;
+use rand::{Rng, RngCore};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug)]
+pub struct BehaviourDimension {
+    pub min: f32,
+    pub max: f32,
+    pub bins: usize,
+}
+
+impl BehaviourDimension {
+    pub fn new(min: f32, max: f32, bins: usize) -> Self {
+        assert!(max > min);
+        assert!(bins > 0);
+
+        Self { min, max, bins }
+    }
+
+    /// Values outside `min..max` are clamped into the edge bins.
+    pub fn bin(&self, value: f32) -> usize {
+        let t = (value - self.min) / (self.max - self.min);
+        ((t * self.bins as f32).max(0.0) as usize).min(self.bins - 1)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Elite<I> {
+    pub individual: I,
+    pub fitness: f32,
+    pub behaviour: Vec<f32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MapElitesArchive<I> {
+    dimensions: Vec<BehaviourDimension>,
+    cells: BTreeMap<Vec<usize>, Elite<I>>,
+}
+
+impl<I> MapElitesArchive<I> {
+    pub fn new(dimensions: Vec<BehaviourDimension>) -> Self {
+        assert!(!dimensions.is_empty());
+
+        Self {
+            dimensions,
+            cells: BTreeMap::new(),
+        }
+    }
+
+    pub fn dimensions(&self) -> &[BehaviourDimension] {
+        &self.dimensions
+    }
+
+    pub fn cell(&self, behaviour: &[f32]) -> Vec<usize> {
+        assert_eq!(behaviour.len(), self.dimensions.len());
+
+        self.dimensions
+            .iter()
+            .zip(behaviour)
+            .map(|(dimension, value)| dimension.bin(*value))
+            .collect()
+    }
+
+    /// Stores `individual` if its cell is empty or holds a less fit elite;
+    /// returns whether it was stored.
+    pub fn insert(&mut self, individual: I, fitness: f32, behaviour: Vec<f32>) -> bool {
+        let cell = self.cell(&behaviour);
+
+        if let Some(elite) = self.cells.get(&cell) {
+            if elite.fitness >= fitness {
+                return false;
+            }
+        }
+
+        self.cells.insert(
+            cell,
+            Elite {
+                individual,
+                fitness,
+                behaviour,
+            },
+        );
+        true
+    }
+
+    pub fn get(&self, cell: &[usize]) -> Option<&Elite<I>> {
+        self.cells.get(cell)
+    }
+
+    pub fn elites(&self) -> impl Iterator<Item = (&[usize], &Elite<I>)> {
+        self.cells
+            .iter()
+            .map(|(cell, elite)| (cell.as_slice(), elite))
+    }
+
+    pub fn random_elite(&self, rng: &mut dyn RngCore) -> Option<&Elite<I>> {
+        self.cells.values().choose(rng)
+    }
+
+    pub fn best(&self) -> Option<&Elite<I>> {
+        self.cells
+            .values()
+            .max_by(|a, b| a.fitness.total_cmp(&b.fitness))
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.dimensions
+            .iter()
+            .map(|dimension| dimension.bins)
+            .product()
+    }
+
+    pub fn statistics(&self) -> MapElitesStatistics {
+        MapElitesStatistics {
+            filled: self.len(),
+            capacity: self.capacity(),
+            qd_score: self.cells.values().map(|elite| elite.fitness).sum(),
+            max_fitness: self.best().map(|elite| elite.fitness).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapElitesStatistics {
+    filled: usize,
+    capacity: usize,
+    qd_score: f32,
+    max_fitness: f32,
+}
+
+impl MapElitesStatistics {
+    pub fn filled(&self) -> usize {
+        self.filled
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn coverage(&self) -> f32 {
+        self.filled as f32 / self.capacity as f32
+    }
+
+    /// Sum of the fitness of every elite in the archive.
+    pub fn qd_score(&self) -> f32 {
+        self.qd_score
+    }
+
+    pub fn max_fitness(&self) -> f32 {
+        self.max_fitness
+    }
+}
+
+pub trait Emitter<I, C>
+where
+    I: Individual<C>,
+{
+    fn emit(&mut self, archive: &MapElitesArchive<I>, params: &C, rng: &mut dyn RngCore) -> I;
+}
+
+/// Mutates a uniformly chosen elite, optionally crossing it with a second
+/// one first; falls back to random individuals while the archive is empty.
+#[derive(Clone, Debug, Default)]
+pub struct RandomElitesEmitter {
+    pub crossover_chance: f32,
+}
+
+impl RandomElitesEmitter {
+    pub fn new(crossover_chance: f32) -> Self {
+        Self { crossover_chance }
+    }
+}
+
+impl<I, C> Emitter<I, C> for RandomElitesEmitter
+where
+    I: Individual<C> + Clone,
+{
+    fn emit(&mut self, archive: &MapElitesArchive<I>, params: &C, rng: &mut dyn RngCore) -> I {
+        let Some(parent) = archive.random_elite(rng) else {
+            return I::random(params, rng);
+        };
+
+        let child = if rng.gen_bool(self.crossover_chance.clamp(0.0, 1.0) as f64) {
+            let other = archive.random_elite(rng).unwrap();
+            offspring(&parent.individual, &other.individual, params, rng)
+        } else {
+            parent.individual.clone()
+        };
+
+        child.mutate(params, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archive() -> MapElitesArchive<&'static str> {
+        MapElitesArchive::new(vec![
+            BehaviourDimension::new(0.0, 1.0, 4),
+            BehaviourDimension::new(0.0, 1.0, 2),
+        ])
+    }
+
+    #[test]
+    fn keeps_fittest_per_cell() {
+        let mut archive = archive();
+
+        assert!(archive.insert("a", 1.0, vec![0.1, 0.1]));
+        assert!(!archive.insert("b", 0.5, vec![0.2, 0.2]));
+        assert!(archive.insert("c", 2.0, vec![0.0, 0.4]));
+        assert!(archive.insert("d", 0.5, vec![0.9, 0.9]));
+
+        assert_eq!(archive.get(&[0, 0]).unwrap().individual, "c");
+        assert_eq!(archive.len(), 2);
+    }
+
+    #[test]
+    fn out_of_range_behaviour_is_clamped() {
+        let archive = archive();
+
+        assert_eq!(archive.cell(&[-1.0, 5.0]), vec![0, 1]);
+        assert_eq!(archive.cell(&[1.0, 0.5]), vec![3, 1]);
+    }
+
+    #[test]
+    fn statistics() {
+        let mut archive = archive();
+        archive.insert("a", 1.0, vec![0.1, 0.1]);
+        archive.insert("b", 3.0, vec![0.9, 0.9]);
+
+        let statistics = archive.statistics();
+
+        assert_eq!(statistics.filled(), 2);
+        assert_eq!(statistics.capacity(), 8);
+        assert_eq!(statistics.coverage(), 0.25);
+        assert_eq!(statistics.qd_score(), 4.0);
+        assert_eq!(statistics.max_fitness(), 3.0);
+    }
+}
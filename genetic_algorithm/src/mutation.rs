@@ -0,0 +1,185 @@
+use crate::optimizer::gaussian;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MutationRates {
+    pub chance: f32,
+    pub coeff: f32,
+}
+
+/// A value that changes with the generation number.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Schedule {
+    Constant(f32),
+    /// Interpolates from `start` to `end` over `generations`, then holds `end`.
+    Linear {
+        start: f32,
+        end: f32,
+        generations: usize,
+    },
+    /// `start * rate^generation`, never going below `min`.
+    Exponential {
+        start: f32,
+        rate: f32,
+        min: f32,
+    },
+}
+
+impl Schedule {
+    pub fn value(&self, generation: usize) -> f32 {
+        match *self {
+            Schedule::Constant(value) => value,
+            Schedule::Linear {
+                start,
+                end,
+                generations,
+            } => {
+                let t = if generations == 0 {
+                    1.0
+                } else {
+                    (generation as f32 / generations as f32).min(1.0)
+                };
+                start + (end - start) * t
+            }
+            Schedule::Exponential { start, rate, min } => {
+                (start * rate.powi(generation.min(i32::MAX as usize) as i32)).max(min)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MutationSchedule {
+    pub chance: Schedule,
+    pub coeff: Schedule,
+}
+
+impl MutationSchedule {
+    pub fn constant(rates: MutationRates) -> Self {
+        Self {
+            chance: Schedule::Constant(rates.chance),
+            coeff: Schedule::Constant(rates.coeff),
+        }
+    }
+
+    pub fn rates(&self, generation: usize) -> MutationRates {
+        MutationRates {
+            chance: self.chance.value(generation),
+            coeff: self.coeff.value(generation),
+        }
+    }
+}
+
+/// Rechenberg's 1/5th success rule: the step size grows while more than a
+/// fifth of the offspring improve on their parents and shrinks otherwise.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OneFifthRule {
+    step: f32,
+    factor: f32,
+    successes: usize,
+    trials: usize,
+}
+
+impl OneFifthRule {
+    pub fn new(step: f32, factor: f32) -> Self {
+        assert!(factor > 0.0 && factor < 1.0);
+
+        Self {
+            step,
+            factor,
+            successes: 0,
+            trials: 0,
+        }
+    }
+
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    pub fn set_step(&mut self, step: f32) {
+        self.step = step;
+    }
+
+    pub fn record(&mut self, success: bool) {
+        self.trials += 1;
+        if success {
+            self.successes += 1;
+        }
+    }
+
+    pub fn success_ratio(&self) -> Option<f32> {
+        if self.trials == 0 {
+            None
+        } else {
+            Some(self.successes as f32 / self.trials as f32)
+        }
+    }
+
+    /// Adjusts the step size from the successes recorded since the last
+    /// call and returns it.
+    pub fn adapt(&mut self) -> f32 {
+        if let Some(ratio) = self.success_ratio() {
+            if ratio > 0.2 {
+                self.step /= self.factor;
+            } else if ratio < 0.2 {
+                self.step *= self.factor;
+            }
+        }
+
+        self.successes = 0;
+        self.trials = 0;
+        self.step
+    }
+}
+
+/// Log-normal self-adaptation of an inherited step size, as in ES.
+pub fn self_adapt(step: f32, learning_rate: f32, rng: &mut dyn RngCore) -> f32 {
+    step * (learning_rate * gaussian(rng)).exp()
+}
+
+/// The usual learning rate for `self_adapt`, `1 / sqrt(dimensions)`.
+pub fn self_adaptation_rate(dimensions: usize) -> f32 {
+    1.0 / (dimensions.max(1) as f32).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn schedules() {
+        let linear = Schedule::Linear {
+            start: 1.0,
+            end: 0.0,
+            generations: 4,
+        };
+        assert_eq!(linear.value(0), 1.0);
+        assert_eq!(linear.value(1), 0.75);
+        assert_eq!(linear.value(10), 0.0);
+
+        let exponential = Schedule::Exponential {
+            start: 1.0,
+            rate: 0.5,
+            min: 0.2,
+        };
+        assert_relative_eq!(exponential.value(1), 0.5);
+        assert_relative_eq!(exponential.value(2), 0.25);
+        assert_relative_eq!(exponential.value(3), 0.2);
+    }
+
+    #[test]
+    fn one_fifth_rule() {
+        let mut rule = OneFifthRule::new(1.0, 0.5);
+
+        rule.record(true);
+        rule.record(false);
+        assert_eq!(rule.adapt(), 2.0);
+
+        (0..10).for_each(|i| rule.record(i == 0));
+        assert_eq!(rule.adapt(), 1.0);
+
+        assert_eq!(rule.adapt(), 1.0);
+    }
+}
@@ -0,0 +1,201 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+pub trait BehaviourDescriptor {
+    fn behaviour(&self) -> Vec<f32>;
+}
+
+impl<B> BehaviourDescriptor for &B
+where
+    B: BehaviourDescriptor,
+{
+    fn behaviour(&self) -> Vec<f32> {
+        (*self).behaviour()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NoveltyArchive {
+    behaviours: VecDeque<Vec<f32>>,
+    k: usize,
+    threshold: f32,
+    capacity: usize,
+}
+
+impl NoveltyArchive {
+    pub fn new(k: usize, threshold: f32, capacity: usize) -> Self {
+        assert!(k > 0);
+
+        Self {
+            behaviours: VecDeque::new(),
+            k,
+            threshold,
+            capacity,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.behaviours.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.behaviours.is_empty()
+    }
+
+    pub fn behaviours(&self) -> impl Iterator<Item = &[f32]> {
+        self.behaviours.iter().map(Vec::as_slice)
+    }
+
+    /// Mean distance to the `k` nearest neighbours among the archive and
+    /// the rest of the current population (`population[index]` is skipped).
+    pub fn novelty(&self, index: usize, population: &[Vec<f32>]) -> f32 {
+        let behaviour = &population[index];
+
+        let mut distances: Vec<f32> = population
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, other)| other)
+            .chain(self.behaviours.iter())
+            .map(|other| distance(behaviour, other))
+            .collect();
+
+        if distances.is_empty() {
+            return 0.0;
+        }
+
+        let k = self.k.min(distances.len());
+        distances.select_nth_unstable_by(k - 1, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        distances[..k].iter().sum::<f32>() / (k as f32)
+    }
+
+    /// Scores every member of `population` and archives the behaviours
+    /// whose novelty exceeds the threshold, oldest entries being evicted
+    /// once the archive is at capacity.
+    pub fn evaluate<B>(&mut self, population: &[B]) -> Vec<f32>
+    where
+        B: BehaviourDescriptor,
+    {
+        let behaviours: Vec<_> = population.iter().map(B::behaviour).collect();
+        let novelties: Vec<_> = (0..behaviours.len())
+            .map(|index| self.novelty(index, &behaviours))
+            .collect();
+
+        for (behaviour, novelty) in behaviours.into_iter().zip(&novelties) {
+            if *novelty > self.threshold {
+                self.insert(behaviour);
+            }
+        }
+
+        novelties
+    }
+
+    /// Archives `behaviour` unconditionally, evicting the oldest entry if
+    /// the archive is full.
+    pub fn insert(&mut self, behaviour: Vec<f32>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.behaviours.len() >= self.capacity {
+            self.behaviours.pop_front();
+        }
+        self.behaviours.push_back(behaviour);
+    }
+}
+
+/// Blends fitness and novelty after normalizing both to `0..=1` over the
+/// population; `novelty_weight` of `0.0` is pure fitness, `1.0` pure novelty.
+pub fn hybrid_scores(fitnesses: &[f32], novelties: &[f32], novelty_weight: f32) -> Vec<f32> {
+    assert_eq!(fitnesses.len(), novelties.len());
+
+    let fitnesses = normalize(fitnesses);
+    let novelties = normalize(novelties);
+    let novelty_weight = novelty_weight.clamp(0.0, 1.0);
+
+    fitnesses
+        .iter()
+        .zip(&novelties)
+        .map(|(fitness, novelty)| (1.0 - novelty_weight) * fitness + novelty_weight * novelty)
+        .collect()
+}
+
+fn normalize(values: &[f32]) -> Vec<f32> {
+    let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let range = max - min;
+
+    values
+        .iter()
+        .map(|value| {
+            if range > 0.0 {
+                (value - min) / range
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+fn distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b) * (a - b))
+        .sum::<f32>()
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    struct Point(f32, f32);
+
+    impl BehaviourDescriptor for Point {
+        fn behaviour(&self) -> Vec<f32> {
+            vec![self.0, self.1]
+        }
+    }
+
+    #[test]
+    fn outlier_is_most_novel() {
+        let mut archive = NoveltyArchive::new(2, f32::INFINITY, 10);
+        let novelties = archive.evaluate(&[
+            Point(0.0, 0.0),
+            Point(0.1, 0.0),
+            Point(0.0, 0.1),
+            Point(5.0, 5.0),
+        ]);
+
+        let most_novel = novelties
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .unwrap()
+            .0;
+
+        assert_eq!(most_novel, 3);
+        assert_relative_eq!(novelties[0], 0.1);
+        assert!(archive.is_empty());
+    }
+
+    #[test]
+    fn archive_is_bounded() {
+        let mut archive = NoveltyArchive::new(1, 0.5, 2);
+        archive.evaluate(&[Point(0.0, 0.0), Point(1.0, 0.0), Point(3.0, 0.0)]);
+
+        assert_eq!(archive.len(), 2);
+        assert_eq!(archive.behaviours().next(), Some(&[1.0, 0.0][..]));
+    }
+
+    #[test]
+    fn hybrid_scores_weighting() {
+        let fitnesses = [0.0, 10.0];
+        let novelties = [4.0, 2.0];
+
+        assert_eq!(hybrid_scores(&fitnesses, &novelties, 0.0), vec![0.0, 1.0]);
+        assert_eq!(hybrid_scores(&fitnesses, &novelties, 1.0), vec![1.0, 0.0]);
+        assert_eq!(hybrid_scores(&fitnesses, &novelties, 0.5), vec![0.5, 0.5]);
+    }
+}
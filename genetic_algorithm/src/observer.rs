@@ -0,0 +1,305 @@
+use crate::*;
+use rand::RngCore;
+
+/// Hooks into every step of `Population::evolve_observed`. All of them do
+/// nothing by default; the mutable ones may intervene in the evolution.
+pub trait EvolutionObserver<I> {
+    fn before_selection(&mut self, _population: &[I]) {}
+
+    /// `parents` are index pairs into `population`, one per child about to
+    /// be bred.
+    fn after_selection(&mut self, _population: &[I], _parents: &mut Vec<(usize, usize)>) {}
+
+    fn on_crossover(&mut self, _parent_a: &I, _parent_b: &I, _child: &mut I) {}
+
+    /// `child` is the crossover child after mutation.
+    fn on_mutation(&mut self, _parent_a: &I, _parent_b: &I, _child: &mut I) {}
+
+    /// `generation` counts the generations evolved with these observers.
+    fn on_generation_end(&mut self, _generation: usize, _statistics: &PopulationStatistics) {}
+
+    /// An individual fitter than any seen before by these observers.
+    fn on_new_best(&mut self, _individual: &I, _fitness: f32) {}
+}
+
+/// The observers of a run, along with the generation count and best
+/// fitness they are told about, and the genealogy of everyone bred.
+pub struct Observers<'a, I> {
+    observers: Vec<Box<dyn EvolutionObserver<I> + 'a>>,
+    generation: usize,
+    best_fitness: f32,
+    genealogy: Option<Genealogy>,
+}
+
+impl<'a, I> Default for Observers<'a, I> {
+    fn default() -> Self {
+        Self {
+            observers: vec![],
+            generation: 0,
+            best_fitness: f32::NEG_INFINITY,
+            genealogy: None,
+        }
+    }
+}
+
+impl<'a, I> Observers<'a, I> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, observer: impl EvolutionObserver<I> + 'a) -> Self {
+        self.push(observer);
+        self
+    }
+
+    pub fn push(&mut self, observer: impl EvolutionObserver<I> + 'a) {
+        self.observers.push(Box::new(observer));
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn best_fitness(&self) -> f32 {
+        self.best_fitness
+    }
+
+    /// Records in `genealogy` every individual bred from now on, along with
+    /// the founders they descend from.
+    pub fn with_genealogy(mut self, genealogy: Genealogy) -> Self {
+        self.genealogy = Some(genealogy);
+        self
+    }
+
+    pub fn genealogy(&self) -> Option<&Genealogy> {
+        self.genealogy.as_ref()
+    }
+
+    pub fn into_genealogy(self) -> Option<Genealogy> {
+        self.genealogy
+    }
+}
+
+impl<'a, I> Observers<'a, I> {
+    pub(crate) fn before_selection(&mut self, population: &[I]) {
+        for observer in &mut self.observers {
+            observer.before_selection(population);
+        }
+    }
+
+    pub(crate) fn after_selection(&mut self, population: &[I], parents: &mut Vec<(usize, usize)>) {
+        for observer in &mut self.observers {
+            observer.after_selection(population, parents);
+        }
+    }
+
+    pub(crate) fn on_crossover(&mut self, parent_a: &I, parent_b: &I, child: &mut I) {
+        for observer in &mut self.observers {
+            observer.on_crossover(parent_a, parent_b, child);
+        }
+    }
+
+    pub(crate) fn on_mutation(&mut self, parent_a: &I, parent_b: &I, child: &mut I) {
+        for observer in &mut self.observers {
+            observer.on_mutation(parent_a, parent_b, child);
+        }
+    }
+
+    /// Records the `children` bred from `pool` as born in the next
+    /// generation, and whoever in `pool` was not recorded yet in this one.
+    pub(crate) fn on_births<C>(&mut self, pool: &[I], children: &[I])
+    where
+        I: Individual<C>,
+    {
+        if let Some(genealogy) = &mut self.genealogy {
+            genealogy.record(pool, self.generation);
+            genealogy.record(children, self.generation + 1);
+        }
+    }
+
+    /// Ends the generation that evaluated `population`.
+    pub(crate) fn on_generation_end<C>(
+        &mut self,
+        population: &[I],
+        statistics: &PopulationStatistics,
+    ) where
+        I: Individual<C>,
+    {
+        let best = population
+            .iter()
+            .map(|individual| (individual, individual.fitness()))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((best, fitness)) = best.filter(|&(_, fitness)| fitness > self.best_fitness) {
+            self.best_fitness = fitness;
+            for observer in &mut self.observers {
+                observer.on_new_best(best, fitness);
+            }
+        }
+
+        for observer in &mut self.observers {
+            observer.on_generation_end(self.generation, statistics);
+        }
+        self.generation += 1;
+    }
+}
+
+/// Crosses `parent_a` with `parent_b`, giving the child an identity of its
+/// own when both parents carry one.
+pub fn offspring<I, C>(parent_a: &I, parent_b: &I, params: &C, rng: &mut dyn RngCore) -> I
+where
+    I: Individual<C>,
+{
+    let mut child = parent_a.crossover(parent_b, params, rng);
+    if let (Some(a), Some(b)) = (parent_a.pedigree(), parent_b.pedigree()) {
+        child.set_pedigree(Pedigree::crossover(a, b, rng));
+    }
+    child
+}
+
+/// Breeds `count` children from `pool` into `children`, with parents picked
+/// by `select` as indices into `pool`, and records their births with the
+/// observers. Returns the parents' indices, two per child.
+pub(crate) fn breed<I, C>(
+    pool: &[I],
+    count: usize,
+    params: &C,
+    rng: &mut dyn RngCore,
+    observers: &mut Observers<'_, I>,
+    mut select: impl FnMut(&mut dyn RngCore) -> usize,
+    children: &mut Vec<I>,
+) -> Vec<usize>
+where
+    I: Individual<C>,
+{
+    observers.before_selection(pool);
+    let mut parents: Vec<_> = (0..count)
+        .map(|_| (select(&mut *rng), select(&mut *rng)))
+        .collect();
+    observers.after_selection(pool, &mut parents);
+
+    children.clear();
+    children.extend(parents.iter().map(|&(a, b)| {
+        let (parent_a, parent_b) = (&pool[a], &pool[b]);
+        let mut child = offspring(parent_a, parent_b, params, rng);
+        observers.on_crossover(parent_a, parent_b, &mut child);
+        let mut child = child.mutate(params, rng);
+        observers.on_mutation(parent_a, parent_b, &mut child);
+        child
+    }));
+    observers.on_births(pool, children);
+
+    parents.into_iter().flat_map(|(a, b)| [a, b]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::cell::RefCell;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Value(f32);
+
+    impl Individual<()> for Value {
+        fn random(_params: &(), rng: &mut dyn RngCore) -> Self {
+            Value(rng.gen_range(1.0..2.0))
+        }
+
+        fn fitness(&self) -> f32 {
+            self.0
+        }
+
+        fn mutate(self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            Value(self.0 + 1.0)
+        }
+
+        fn crossover(&self, other: &Self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            Value((self.0 + other.0) / 2.0)
+        }
+    }
+
+    /// Logs every hook, and breeds only from the fittest individual.
+    struct Elitist<'a>(&'a RefCell<Vec<String>>);
+
+    impl EvolutionObserver<Value> for Elitist<'_> {
+        fn before_selection(&mut self, population: &[Value]) {
+            self.0
+                .borrow_mut()
+                .push(format!("select {}", population.len()));
+        }
+
+        fn after_selection(&mut self, population: &[Value], parents: &mut Vec<(usize, usize)>) {
+            let best = (0..population.len())
+                .max_by(|&a, &b| population[a].0.total_cmp(&population[b].0))
+                .unwrap();
+            parents.fill((best, best));
+        }
+
+        fn on_crossover(&mut self, parent_a: &Value, parent_b: &Value, child: &mut Value) {
+            assert_eq!(parent_a, parent_b);
+            assert_eq!(child, parent_a);
+        }
+
+        fn on_mutation(&mut self, parent_a: &Value, _parent_b: &Value, child: &mut Value) {
+            assert_eq!(child.0, parent_a.0 + 1.0);
+        }
+
+        fn on_generation_end(&mut self, generation: usize, statistics: &PopulationStatistics) {
+            self.0.borrow_mut().push(format!(
+                "end {generation} {}",
+                statistics.selection().unwrap().unique_parents()
+            ));
+        }
+
+        fn on_new_best(&mut self, _individual: &Value, fitness: f32) {
+            self.0
+                .borrow_mut()
+                .push(format!("best {}", fitness.floor()));
+        }
+    }
+
+    #[test]
+    fn hooks() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let log = RefCell::new(vec![]);
+        let mut observers = Observers::new().with(Elitist(&log));
+
+        let mut population = RouletteWheelPopulation::random(4, &(), &mut rng);
+        for _ in 0..2 {
+            population.evolve_observed(&(), &mut rng, &mut observers);
+        }
+
+        assert_eq!(observers.generation(), 2);
+        assert!(population.population.iter().all(|value| value.0 >= 3.0));
+        drop(observers);
+        assert_eq!(
+            log.into_inner(),
+            ["select 4", "best 1", "end 0 1", "select 4", "best 2", "end 1 1"]
+        );
+    }
+
+    #[test]
+    fn breeding_records_lineage() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let config = BitStringConfig::new(8, |bits| bits.iter().filter(|&&bit| bit).count() as f32);
+        let mut observers = Observers::new().with_genealogy(Genealogy::new());
+
+        let mut population = RouletteWheelPopulation::<BitString>::random(10, &config, &mut rng);
+        for _ in 0..3 {
+            population.evolve_observed(&config, &mut rng, &mut observers);
+        }
+
+        let genealogy = observers.into_genealogy().unwrap();
+        assert_eq!(genealogy.len(), 40);
+        for child in &population.population {
+            let record = genealogy.get(child.pedigree().unwrap().id).unwrap();
+            assert_eq!(record.generation, 3);
+            assert_eq!(record.operator, Operator::CrossoverMutation);
+            assert!(record
+                .parents
+                .iter()
+                .all(|&parent| genealogy.get(parent).unwrap().generation == 2));
+        }
+    }
+}
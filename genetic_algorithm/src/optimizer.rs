@@ -0,0 +1,199 @@
+mod cma_es;
+mod differential_evolution;
+mod evolution_strategy;
+mod natural_evolution_strategy;
+
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+pub use cma_es::*;
+pub use differential_evolution::*;
+pub use evolution_strategy::*;
+pub use natural_evolution_strategy::*;
+
+/// Ask/tell interface for optimizers over real-valued parameter vectors.
+/// Fitness is maximized, as everywhere else in this crate.
+pub trait Optimizer {
+    fn dimensions(&self) -> usize;
+
+    /// Candidates to be evaluated this generation.
+    fn ask(&mut self, rng: &mut dyn RngCore) -> Vec<Vec<f32>>;
+
+    /// Reports the fitness of the candidates returned by the last `ask`,
+    /// in the same order.
+    fn tell(&mut self, candidates: &[Vec<f32>], fitnesses: &[f32]);
+
+    fn best(&self) -> Option<(&[f32], f32)>;
+
+    /// Everything needed to carry on from here, for optimizers that can be
+    /// checkpointed.
+    fn snapshot(&self) -> Option<OptimizerState> {
+        None
+    }
+}
+
+/// A snapshot of one of this crate's optimizers, mid-run included.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptimizerState {
+    CmaEs(Box<CmaEs>),
+    EvolutionStrategy(EvolutionStrategy),
+    NaturalEvolutionStrategy(NaturalEvolutionStrategy),
+    DifferentialEvolution(DifferentialEvolution),
+}
+
+impl OptimizerState {
+    pub fn into_optimizer(self) -> Box<dyn Optimizer + Send> {
+        match self {
+            OptimizerState::CmaEs(optimizer) => optimizer,
+            OptimizerState::EvolutionStrategy(optimizer) => Box::new(optimizer),
+            OptimizerState::NaturalEvolutionStrategy(optimizer) => Box::new(optimizer),
+            OptimizerState::DifferentialEvolution(optimizer) => Box::new(optimizer),
+        }
+    }
+}
+
+pub(crate) fn gaussian(rng: &mut dyn RngCore) -> f32 {
+    let u1: f32 = rng.gen_range(f32::EPSILON..1.0);
+    let u2: f32 = rng.gen();
+
+    (-2.0 * u1.ln()).sqrt() * (TAU * u2).cos()
+}
+
+/// Indices of `fitnesses` from the fittest to the least fit.
+pub(crate) fn ranked(fitnesses: &[f32]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..fitnesses.len()).collect();
+    order.sort_by(|&a, &b| fitnesses[b].total_cmp(&fitnesses[a]));
+    order
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct BestCandidate(Option<(Vec<f32>, f32)>);
+
+impl BestCandidate {
+    pub(crate) fn update(&mut self, candidates: &[Vec<f32>], fitnesses: &[f32]) {
+        let Some(&fittest) = ranked(fitnesses).first() else {
+            return;
+        };
+
+        if self
+            .0
+            .as_ref()
+            .is_none_or(|(_, best)| fitnesses[fittest] > *best)
+        {
+            self.0 = Some((candidates[fittest].clone(), fitnesses[fittest]));
+        }
+    }
+
+    pub(crate) fn get(&self) -> Option<(&[f32], f32)> {
+        self.0
+            .as_ref()
+            .map(|(candidate, fitness)| (candidate.as_slice(), *fitness))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn sphere(x: &[f32]) -> f32 {
+        -x.iter().map(|x| (x - 1.0) * (x - 1.0)).sum::<f32>()
+    }
+
+    fn run(mut optimizer: impl Optimizer, generations: usize) -> f32 {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        for _ in 0..generations {
+            let candidates = optimizer.ask(&mut rng);
+            let fitnesses: Vec<_> = candidates.iter().map(|c| sphere(c)).collect();
+            optimizer.tell(&candidates, &fitnesses);
+        }
+
+        optimizer.best().unwrap().1
+    }
+
+    #[test]
+    fn snapshots_carry_on() {
+        let optimizers: Vec<Box<dyn Optimizer + Send>> = vec![
+            Box::new(CmaEs::new(vec![0.0; 5], 0.5, 10)),
+            Box::new(
+                EvolutionStrategy::one_plus_lambda(vec![0.0; 5], 10, 0.5).with_one_fifth_rule(0.85),
+            ),
+            Box::new(NaturalEvolutionStrategy::new(vec![0.0; 5], 0.1, 0.05, 20)),
+            Box::new(DifferentialEvolution::new(vec![0.0; 5], 1.0, 20)),
+        ];
+
+        for mut optimizer in optimizers {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let step = |optimizer: &mut dyn Optimizer, rng: &mut ChaCha8Rng| {
+                let candidates = optimizer.ask(rng);
+                let fitnesses: Vec<_> = candidates.iter().map(|c| sphere(c)).collect();
+                optimizer.tell(&candidates, &fitnesses);
+                candidates
+            };
+            for _ in 0..5 {
+                step(optimizer.as_mut(), &mut rng);
+            }
+
+            let mut resumed = optimizer.snapshot().unwrap().into_optimizer();
+            let mut resumed_rng = rng.clone();
+            for _ in 0..5 {
+                assert_eq!(
+                    step(optimizer.as_mut(), &mut rng),
+                    step(resumed.as_mut(), &mut resumed_rng)
+                );
+            }
+            assert_eq!(optimizer.best(), resumed.best());
+        }
+    }
+
+    #[test]
+    fn cma_es() {
+        assert!(run(CmaEs::new(vec![0.0; 5], 0.5, 10), 100) > -1e-3);
+    }
+
+    #[test]
+    fn one_plus_lambda() {
+        assert!(
+            run(
+                EvolutionStrategy::one_plus_lambda(vec![0.0; 5], 10, 0.1),
+                200
+            ) > -0.05
+        );
+    }
+
+    #[test]
+    fn one_plus_lambda_with_one_fifth_rule() {
+        let optimizer =
+            EvolutionStrategy::one_plus_lambda(vec![0.0; 5], 10, 0.5).with_one_fifth_rule(0.85);
+        assert!(run(optimizer, 200) > -1e-3);
+    }
+
+    #[test]
+    fn mu_comma_lambda() {
+        assert!(
+            run(
+                EvolutionStrategy::mu_comma_lambda(vec![0.0; 5], 3, 12, 0.05),
+                200
+            ) > -0.05
+        );
+    }
+
+    #[test]
+    fn natural_evolution_strategy() {
+        assert!(
+            run(
+                NaturalEvolutionStrategy::new(vec![0.0; 5], 0.1, 0.05, 20),
+                300
+            ) > -0.05
+        );
+    }
+
+    #[test]
+    fn differential_evolution() {
+        assert!(run(DifferentialEvolution::new(vec![0.0; 5], 1.0, 20), 200) > -1e-3);
+    }
+}
@@ -0,0 +1,245 @@
+use crate::optimizer::*;
+use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
+
+/// Covariance matrix adaptation evolution strategy, following Hansen's
+/// "The CMA Evolution Strategy: A Tutorial" with the default parameters.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CmaEs {
+    lambda: usize,
+    weights: DVector<f64>,
+    mu_eff: f64,
+    cc: f64,
+    cs: f64,
+    c1: f64,
+    cmu: f64,
+    damps: f64,
+    chi_n: f64,
+    mean: DVector<f64>,
+    sigma: f64,
+    covariance: DMatrix<f64>,
+    pc: DVector<f64>,
+    ps: DVector<f64>,
+    /// The covariance as of its last decomposition into `basis` and
+    /// `scales`, which are rebuilt from it when loaded.
+    decomposed: DMatrix<f64>,
+    #[serde(skip)]
+    basis: DMatrix<f64>,
+    #[serde(skip)]
+    scales: DVector<f64>,
+    /// Generation of the last decomposition.
+    eigen_generation: usize,
+    generation: usize,
+    best: BestCandidate,
+}
+
+impl CmaEs {
+    pub fn new(initial: Vec<f32>, sigma: f32, lambda: usize) -> Self {
+        let n = initial.len();
+        assert!(n > 0);
+        assert!(lambda >= 2);
+
+        let nf = n as f64;
+        let mu = lambda / 2;
+        let weights = DVector::from_iterator(
+            mu,
+            (0..mu).map(|i| ((lambda as f64 + 1.0) / 2.0).ln() - ((i + 1) as f64).ln()),
+        );
+        let weights = &weights / weights.sum();
+        let mu_eff = 1.0 / weights.map(|w| w * w).sum();
+
+        let cc = (4.0 + mu_eff / nf) / (nf + 4.0 + 2.0 * mu_eff / nf);
+        let cs = (mu_eff + 2.0) / (nf + mu_eff + 5.0);
+        let c1 = 2.0 / ((nf + 1.3).powi(2) + mu_eff);
+        let cmu =
+            (1.0 - c1).min(2.0 * (mu_eff - 2.0 + 1.0 / mu_eff) / ((nf + 2.0).powi(2) + mu_eff));
+        let damps = 1.0 + 2.0 * (((mu_eff - 1.0) / (nf + 1.0)).sqrt() - 1.0).max(0.0) + cs;
+        let chi_n = nf.sqrt() * (1.0 - 1.0 / (4.0 * nf) + 1.0 / (21.0 * nf * nf));
+
+        Self {
+            lambda,
+            weights,
+            mu_eff,
+            cc,
+            cs,
+            c1,
+            cmu,
+            damps,
+            chi_n,
+            mean: DVector::from_iterator(n, initial.iter().map(|&x| x as f64)),
+            sigma: sigma as f64,
+            covariance: DMatrix::identity(n, n),
+            pc: DVector::zeros(n),
+            ps: DVector::zeros(n),
+            decomposed: DMatrix::identity(n, n),
+            basis: DMatrix::identity(n, n),
+            scales: DVector::from_element(n, 1.0),
+            eigen_generation: 0,
+            generation: 0,
+            best: BestCandidate::default(),
+        }
+    }
+
+    pub fn mean(&self) -> Vec<f32> {
+        self.mean.iter().map(|&x| x as f32).collect()
+    }
+
+    pub fn sigma(&self) -> f32 {
+        self.sigma as f32
+    }
+
+    /// Decomposes the covariance again only once it has had
+    /// `lambda / (c1 + cmu) / n / 10` generations to drift, as the tutorial
+    /// suggests, so most generations skip the O(n³) decomposition.
+    fn update_eigensystem(&mut self) {
+        let gap = self.lambda as f64 / (self.c1 + self.cmu) / self.dimensions() as f64 / 10.0;
+        if ((self.generation - self.eigen_generation) as f64) < gap {
+            return;
+        }
+
+        self.eigen_generation = self.generation;
+        self.covariance = (&self.covariance + self.covariance.transpose()) / 2.0;
+        self.decomposed = self.covariance.clone();
+        self.decompose();
+    }
+
+    fn decompose(&mut self) {
+        let eigen = self.decomposed.clone().symmetric_eigen();
+
+        self.scales = eigen.eigenvalues.map(|v| v.max(1e-20).sqrt());
+        self.basis = eigen.eigenvectors;
+    }
+
+    /// Rebuilds the eigensystem a loaded strategy was saved without.
+    fn restore_eigensystem(&mut self) {
+        if self.scales.len() != self.dimensions() {
+            self.decompose();
+        }
+    }
+}
+
+impl Optimizer for CmaEs {
+    fn dimensions(&self) -> usize {
+        self.mean.len()
+    }
+
+    fn ask(&mut self, rng: &mut dyn RngCore) -> Vec<Vec<f32>> {
+        self.restore_eigensystem();
+        let n = self.dimensions();
+
+        (0..self.lambda)
+            .map(|_| {
+                let z = DVector::from_iterator(n, (0..n).map(|_| gaussian(rng) as f64));
+                let y = &self.basis * z.component_mul(&self.scales);
+                (&self.mean + self.sigma * y)
+                    .iter()
+                    .map(|&x| x as f32)
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn tell(&mut self, candidates: &[Vec<f32>], fitnesses: &[f32]) {
+        self.restore_eigensystem();
+        self.best.update(candidates, fitnesses);
+        self.generation += 1;
+
+        let n = self.dimensions();
+        let steps: Vec<DVector<f64>> = ranked(fitnesses)
+            .into_iter()
+            .take(self.weights.len())
+            .map(|i| {
+                let x = DVector::from_iterator(n, candidates[i].iter().map(|&x| x as f64));
+                (x - &self.mean) / self.sigma
+            })
+            .collect();
+
+        let mut step = DVector::zeros(n);
+        for (w, y) in self.weights.iter().zip(&steps) {
+            step += *w * y;
+        }
+        self.mean += self.sigma * &step;
+
+        let inv_sqrt = &self.basis
+            * DMatrix::from_diagonal(&self.scales.map(|d| 1.0 / d))
+            * self.basis.transpose();
+        self.ps = (1.0 - self.cs) * &self.ps
+            + (self.cs * (2.0 - self.cs) * self.mu_eff).sqrt() * (inv_sqrt * &step);
+
+        let ps_norm = self.ps.norm();
+        let hsig =
+            ps_norm / (1.0 - (1.0 - self.cs).powi(2 * self.generation as i32)).sqrt() / self.chi_n
+                < 1.4 + 2.0 / (n as f64 + 1.0);
+        let hsig = if hsig { 1.0 } else { 0.0 };
+
+        self.pc = (1.0 - self.cc) * &self.pc
+            + hsig * (self.cc * (2.0 - self.cc) * self.mu_eff).sqrt() * &step;
+
+        let mut rank_mu = DMatrix::zeros(n, n);
+        for (w, y) in self.weights.iter().zip(&steps) {
+            rank_mu += *w * y * y.transpose();
+        }
+
+        let rank_one = &self.pc * self.pc.transpose()
+            + (1.0 - hsig) * self.cc * (2.0 - self.cc) * &self.covariance;
+        self.covariance =
+            (1.0 - self.c1 - self.cmu) * &self.covariance + self.c1 * rank_one + self.cmu * rank_mu;
+
+        self.sigma *= ((self.cs / self.damps) * (ps_norm / self.chi_n - 1.0)).exp();
+
+        self.update_eigensystem();
+    }
+
+    fn best(&self) -> Option<(&[f32], f32)> {
+        self.best.get()
+    }
+
+    fn snapshot(&self) -> Option<OptimizerState> {
+        Some(OptimizerState::CmaEs(Box::new(self.clone())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn step(cma_es: &mut CmaEs, rng: &mut ChaCha8Rng) -> Vec<Vec<f32>> {
+        let candidates = cma_es.ask(rng);
+        let fitnesses: Vec<_> = candidates
+            .iter()
+            .map(|c| -c.iter().map(|x| x * x).sum::<f32>())
+            .collect();
+        cma_es.tell(&candidates, &fitnesses);
+        candidates
+    }
+
+    #[test]
+    fn decomposes_every_few_generations() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut cma_es = CmaEs::new(vec![1.0; 50], 0.5, 10);
+
+        let mut decompositions = 0;
+        for _ in 0..15 {
+            let last = cma_es.eigen_generation;
+            step(&mut cma_es, &mut rng);
+            decompositions += usize::from(cma_es.eigen_generation != last);
+        }
+        assert_eq!(decompositions, 1);
+
+        // Loaded without its eigensystem, it samples as if never saved.
+        let mut loaded = CmaEs {
+            basis: DMatrix::default(),
+            scales: DVector::default(),
+            ..cma_es.clone()
+        };
+        let mut loaded_rng = rng.clone();
+        for _ in 0..10 {
+            assert_eq!(
+                step(&mut cma_es, &mut rng),
+                step(&mut loaded, &mut loaded_rng)
+            );
+        }
+    }
+}
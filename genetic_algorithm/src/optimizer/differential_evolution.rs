@@ -0,0 +1,114 @@
+use crate::optimizer::*;
+use serde::{Deserialize, Serialize};
+
+/// DE/rand/1/bin. The first `ask` returns the initial population itself;
+/// afterwards every candidate is the trial vector for the member at the
+/// same index, which it replaces if at least as fit.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DifferentialEvolution {
+    population: Vec<Vec<f32>>,
+    fitnesses: Option<Vec<f32>>,
+    pub differential_weight: f32,
+    pub crossover_rate: f32,
+    spread: f32,
+    best: BestCandidate,
+}
+
+impl DifferentialEvolution {
+    pub fn new(initial: Vec<f32>, spread: f32, population_size: usize) -> Self {
+        assert!(population_size >= 4);
+
+        Self {
+            population: vec![initial; population_size],
+            fitnesses: None,
+            differential_weight: 0.8,
+            crossover_rate: 0.9,
+            spread,
+            best: BestCandidate::default(),
+        }
+    }
+
+    pub fn population(&self) -> &[Vec<f32>] {
+        &self.population
+    }
+
+    fn trial(&self, target: usize, rng: &mut dyn RngCore) -> Vec<f32> {
+        let len = self.population.len();
+        let mut pick = |excluded: &[usize]| loop {
+            let i = rng.gen_range(0..len);
+            if !excluded.contains(&i) {
+                return i;
+            }
+        };
+
+        let r1 = pick(&[target]);
+        let r2 = pick(&[target, r1]);
+        let r3 = pick(&[target, r1, r2]);
+
+        let dimensions = self.dimensions();
+        let forced = rng.gen_range(0..dimensions);
+
+        (0..dimensions)
+            .map(|d| {
+                if d == forced || rng.gen::<f32>() < self.crossover_rate {
+                    self.population[r1][d]
+                        + self.differential_weight
+                            * (self.population[r2][d] - self.population[r3][d])
+                } else {
+                    self.population[target][d]
+                }
+            })
+            .collect()
+    }
+}
+
+impl Optimizer for DifferentialEvolution {
+    fn dimensions(&self) -> usize {
+        self.population[0].len()
+    }
+
+    fn ask(&mut self, rng: &mut dyn RngCore) -> Vec<Vec<f32>> {
+        if self.fitnesses.is_none() {
+            for member in &mut self.population {
+                for x in member.iter_mut() {
+                    *x += self.spread * gaussian(rng);
+                }
+            }
+
+            return self.population.clone();
+        }
+
+        (0..self.population.len())
+            .map(|target| self.trial(target, rng))
+            .collect()
+    }
+
+    fn tell(&mut self, candidates: &[Vec<f32>], fitnesses: &[f32]) {
+        assert_eq!(candidates.len(), self.population.len());
+
+        self.best.update(candidates, fitnesses);
+
+        match &mut self.fitnesses {
+            None => {
+                self.population = candidates.to_vec();
+                self.fitnesses = Some(fitnesses.to_vec());
+            }
+            Some(current) => {
+                for (i, (candidate, &fitness)) in candidates.iter().zip(fitnesses).enumerate() {
+                    if fitness >= current[i] {
+                        self.population[i] = candidate.clone();
+                        current[i] = fitness;
+                    }
+                }
+            }
+        }
+    }
+
+    fn best(&self) -> Option<(&[f32], f32)> {
+        self.best.get()
+    }
+
+    fn snapshot(&self) -> Option<OptimizerState> {
+        Some(OptimizerState::DifferentialEvolution(self.clone()))
+    }
+}
@@ -0,0 +1,110 @@
+use crate::optimizer::*;
+use crate::OneFifthRule;
+use serde::{Deserialize, Serialize};
+
+/// (μ,λ) and (μ+λ) evolution strategies with isotropic Gaussian mutation;
+/// (1+λ) is the plus variant with a single parent.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EvolutionStrategy {
+    parents: Vec<(Vec<f32>, f32)>,
+    mu: usize,
+    lambda: usize,
+    plus: bool,
+    pub sigma: f32,
+    one_fifth_rule: Option<OneFifthRule>,
+    best: BestCandidate,
+}
+
+impl EvolutionStrategy {
+    pub fn new(initial: Vec<f32>, mu: usize, lambda: usize, plus: bool, sigma: f32) -> Self {
+        assert!(mu > 0);
+        assert!(lambda >= mu || plus);
+
+        Self {
+            parents: vec![(initial, f32::NEG_INFINITY)],
+            mu,
+            lambda,
+            plus,
+            sigma,
+            one_fifth_rule: None,
+            best: BestCandidate::default(),
+        }
+    }
+
+    pub fn one_plus_lambda(initial: Vec<f32>, lambda: usize, sigma: f32) -> Self {
+        Self::new(initial, 1, lambda, true, sigma)
+    }
+
+    pub fn mu_comma_lambda(initial: Vec<f32>, mu: usize, lambda: usize, sigma: f32) -> Self {
+        Self::new(initial, mu, lambda, false, sigma)
+    }
+
+    /// Adapts `sigma` after every generation with the 1/5th success rule,
+    /// a success being a candidate that beats the best of its parents.
+    pub fn with_one_fifth_rule(mut self, factor: f32) -> Self {
+        self.one_fifth_rule = Some(OneFifthRule::new(self.sigma, factor));
+        self
+    }
+
+    pub fn parents(&self) -> impl Iterator<Item = &[f32]> {
+        self.parents.iter().map(|(parent, _)| parent.as_slice())
+    }
+}
+
+impl Optimizer for EvolutionStrategy {
+    fn dimensions(&self) -> usize {
+        self.parents[0].0.len()
+    }
+
+    fn ask(&mut self, rng: &mut dyn RngCore) -> Vec<Vec<f32>> {
+        (0..self.lambda)
+            .map(|i| {
+                let (parent, _) = &self.parents[i % self.parents.len()];
+                parent
+                    .iter()
+                    .map(|x| x + self.sigma * gaussian(rng))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn tell(&mut self, candidates: &[Vec<f32>], fitnesses: &[f32]) {
+        self.best.update(candidates, fitnesses);
+
+        if let Some(rule) = &mut self.one_fifth_rule {
+            let parent_best = self
+                .parents
+                .iter()
+                .map(|(_, fitness)| *fitness)
+                .fold(f32::NEG_INFINITY, f32::max);
+
+            if parent_best.is_finite() {
+                for fitness in fitnesses {
+                    rule.record(*fitness > parent_best);
+                }
+                self.sigma = rule.adapt();
+            }
+        }
+
+        let mut pool: Vec<(Vec<f32>, f32)> = candidates
+            .iter()
+            .cloned()
+            .zip(fitnesses.iter().copied())
+            .collect();
+        if self.plus {
+            pool.append(&mut self.parents);
+        }
+
+        pool.sort_by(|a, b| b.1.total_cmp(&a.1));
+        pool.truncate(self.mu);
+        self.parents = pool;
+    }
+
+    fn best(&self) -> Option<(&[f32], f32)> {
+        self.best.get()
+    }
+
+    fn snapshot(&self) -> Option<OptimizerState> {
+        Some(OptimizerState::EvolutionStrategy(self.clone()))
+    }
+}
@@ -0,0 +1,98 @@
+use crate::optimizer::*;
+use serde::{Deserialize, Serialize};
+
+/// OpenAI-style natural evolution strategy: antithetic Gaussian sampling
+/// around a single mean, centered-rank fitness shaping and a plain gradient
+/// step.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NaturalEvolutionStrategy {
+    mean: Vec<f32>,
+    pub sigma: f32,
+    pub learning_rate: f32,
+    population_size: usize,
+    best: BestCandidate,
+}
+
+impl NaturalEvolutionStrategy {
+    pub fn new(initial: Vec<f32>, sigma: f32, learning_rate: f32, population_size: usize) -> Self {
+        assert!(sigma > 0.0);
+        assert!(population_size >= 2);
+
+        Self {
+            mean: initial,
+            sigma,
+            learning_rate,
+            population_size,
+            best: BestCandidate::default(),
+        }
+    }
+
+    pub fn mean(&self) -> &[f32] {
+        &self.mean
+    }
+}
+
+impl Optimizer for NaturalEvolutionStrategy {
+    fn dimensions(&self) -> usize {
+        self.mean.len()
+    }
+
+    fn ask(&mut self, rng: &mut dyn RngCore) -> Vec<Vec<f32>> {
+        let mut candidates = Vec::with_capacity(self.population_size);
+
+        while candidates.len() < self.population_size {
+            let noise: Vec<f32> = self.mean.iter().map(|_| gaussian(rng)).collect();
+
+            for sign in [1.0, -1.0] {
+                if candidates.len() < self.population_size {
+                    candidates.push(
+                        self.mean
+                            .iter()
+                            .zip(&noise)
+                            .map(|(m, e)| m + sign * self.sigma * e)
+                            .collect(),
+                    );
+                }
+            }
+        }
+
+        candidates
+    }
+
+    fn tell(&mut self, candidates: &[Vec<f32>], fitnesses: &[f32]) {
+        self.best.update(candidates, fitnesses);
+
+        let len = candidates.len();
+        if len < 2 {
+            return;
+        }
+
+        let mut shaped = vec![0.0; len];
+        for (rank, i) in ranked(fitnesses).into_iter().rev().enumerate() {
+            shaped[i] = rank as f32 / (len - 1) as f32 - 0.5;
+        }
+
+        let scale = self.learning_rate / (len as f32 * self.sigma);
+        let gradient: Vec<f32> = (0..self.mean.len())
+            .map(|d| {
+                candidates
+                    .iter()
+                    .zip(&shaped)
+                    .map(|(candidate, f)| f * (candidate[d] - self.mean[d]) / self.sigma)
+                    .sum::<f32>()
+            })
+            .collect();
+
+        for (m, g) in self.mean.iter_mut().zip(gradient) {
+            *m += scale * g;
+        }
+    }
+
+    fn best(&self) -> Option<(&[f32], f32)> {
+        self.best.get()
+    }
+
+    fn snapshot(&self) -> Option<OptimizerState> {
+        Some(OptimizerState::NaturalEvolutionStrategy(self.clone()))
+    }
+}
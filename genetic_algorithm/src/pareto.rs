@@ -0,0 +1,144 @@
+use std::cmp::Ordering;
+
+/// All objectives are maximized.
+pub fn dominates(a: &[f32], b: &[f32]) -> bool {
+    let mut strictly_better = false;
+
+    for (a, b) in a.iter().zip(b) {
+        if a < b {
+            return false;
+        }
+        if a > b {
+            strictly_better = true;
+        }
+    }
+
+    strictly_better
+}
+
+/// Splits `objectives` into successive Pareto fronts of indices, the first
+/// front being the non-dominated set.
+pub fn non_dominated_sort(objectives: &[Vec<f32>]) -> Vec<Vec<usize>> {
+    let len = objectives.len();
+    let mut dominated_by: Vec<Vec<usize>> = vec![vec![]; len];
+    let mut domination_count = vec![0usize; len];
+    let mut fronts = vec![vec![]];
+
+    for p in 0..len {
+        for q in 0..len {
+            if dominates(&objectives[p], &objectives[q]) {
+                dominated_by[p].push(q);
+            } else if dominates(&objectives[q], &objectives[p]) {
+                domination_count[p] += 1;
+            }
+        }
+
+        if domination_count[p] == 0 {
+            fronts[0].push(p);
+        }
+    }
+
+    let mut current = 0;
+    while !fronts[current].is_empty() {
+        let mut next = vec![];
+
+        for &p in &fronts[current] {
+            for &q in &dominated_by[p] {
+                domination_count[q] -= 1;
+                if domination_count[q] == 0 {
+                    next.push(q);
+                }
+            }
+        }
+
+        current += 1;
+        fronts.push(next);
+    }
+
+    fronts.pop();
+    fronts
+}
+
+/// Crowding distance of each member of `front`, in the same order.
+/// Boundary solutions get `f32::INFINITY`.
+pub fn crowding_distance(objectives: &[Vec<f32>], front: &[usize]) -> Vec<f32> {
+    let len = front.len();
+    let mut distances = vec![0.0; len];
+
+    if len <= 2 {
+        return vec![f32::INFINITY; len];
+    }
+
+    let dimensions = objectives[front[0]].len();
+    let columns = (0..dimensions).map(|m| {
+        front
+            .iter()
+            .map(|&i| objectives[i][m])
+            .collect::<Vec<f32>>()
+    });
+
+    for values in columns {
+        let mut order: Vec<usize> = (0..len).collect();
+        order.sort_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap_or(Ordering::Equal));
+
+        let min = values[order[0]];
+        let max = values[order[len - 1]];
+        distances[order[0]] = f32::INFINITY;
+        distances[order[len - 1]] = f32::INFINITY;
+
+        if max - min <= 0.0 {
+            continue;
+        }
+
+        for window in order.windows(3) {
+            distances[window[1]] += (values[window[2]] - values[window[0]]) / (max - min);
+        }
+    }
+
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn domination() {
+        assert!(dominates(&[1.0, 2.0], &[1.0, 1.0]));
+        assert!(!dominates(&[1.0, 1.0], &[1.0, 1.0]));
+        assert!(!dominates(&[2.0, 0.0], &[0.0, 2.0]));
+    }
+
+    #[test]
+    fn sorts_into_fronts() {
+        let objectives = vec![
+            vec![1.0, 1.0],
+            vec![3.0, 1.0],
+            vec![1.0, 3.0],
+            vec![2.0, 2.0],
+            vec![0.0, 0.0],
+        ];
+
+        let mut fronts = non_dominated_sort(&objectives);
+        fronts.iter_mut().for_each(|front| front.sort());
+
+        assert_eq!(fronts, vec![vec![1, 2, 3], vec![0], vec![4]]);
+    }
+
+    #[test]
+    fn crowding_prefers_boundaries() {
+        let objectives = vec![
+            vec![0.0, 4.0],
+            vec![1.0, 3.0],
+            vec![3.0, 1.0],
+            vec![4.0, 0.0],
+        ];
+
+        let distances = crowding_distance(&objectives, &[0, 1, 2, 3]);
+
+        assert_eq!(distances[0], f32::INFINITY);
+        assert_eq!(distances[3], f32::INFINITY);
+        assert_eq!(distances[1], distances[2]);
+        assert!(distances[1].is_finite());
+    }
+}
@@ -1,36 +1,118 @@
+mod alps;
+mod nsga2;
 mod roulette_wheel;
+mod steady_state;
 
 use crate::individual::Individual;
-use crate::PopulationStatistics;
+use crate::{Observers, PopulationStatistics};
+use rand::distributions::{Distribution, WeightedIndex};
 use rand::RngCore;
+use serde::{Deserialize, Serialize};
 
+pub use alps::*;
+pub use nsga2::*;
 pub use roulette_wheel::*;
+pub use steady_state::*;
 
+/// A generation of individuals that breeds its successors in place. Usable
+/// as a trait object, so the kind of population can be picked at runtime.
 pub trait Population<I, C>
 where
     I: Individual<C>,
-    C: Clone,
 {
-    fn new(individuals: Vec<I>, config: &C) -> Box<Self>;
-    fn get_config(&self) -> &C;
-    fn size(&self) -> usize;
-    fn random(size: usize, params: &C, rng: &mut dyn RngCore) -> Self;
+    fn random(size: usize, params: &C, rng: &mut dyn RngCore) -> Self
+    where
+        Self: Sized;
+
+    fn individuals(&self) -> &[I];
+    fn individuals_mut(&mut self) -> &mut [I];
+
+    /// Starts over from `individuals`, e.g. bred by another optimizer.
+    fn replace(&mut self, individuals: Vec<I>);
+
     fn select(&self, params: &C, rng: &mut dyn RngCore) -> &I;
-    fn get_population(&self) -> &Vec<I>;
-
-    fn evolve(&self, params: &C, rng: &mut dyn RngCore) -> (Box<Self>, PopulationStatistics) {
-        let new_population: Vec<I> = (0..self.size())
-            .map(|_| {
-                let parent_a = self.select(params, rng);
-                let parent_b = self.select(params, rng);
-
-                parent_a
-                    .crossover(parent_b, params, rng)
-                    .mutate(params, rng)
-            })
-            .collect();
-        let stats = PopulationStatistics::new(self.get_population());
-
-        (Self::new(new_population, self.get_config()), stats)
+
+    /// What breeding depends on beyond `individuals`, for checkpoints;
+    /// `None` for populations that cannot be resumed.
+    fn snapshot(&self) -> Option<PopulationState<I>> {
+        None
+    }
+
+    /// Picks up from a `snapshot` taken of a population with the same
+    /// individuals.
+    fn restore(&mut self, _state: PopulationState<I>) {}
+
+    /// Replaces the current generation with its offspring and returns the
+    /// statistics of the generation replaced.
+    fn evolve_observed(
+        &mut self,
+        params: &C,
+        rng: &mut dyn RngCore,
+        observers: &mut Observers<'_, I>,
+    ) -> PopulationStatistics;
+
+    fn size(&self) -> usize {
+        self.individuals().len()
+    }
+
+    fn evolve(&mut self, params: &C, rng: &mut dyn RngCore) -> PopulationStatistics {
+        self.evolve_observed(params, rng, &mut Observers::new())
+    }
+}
+
+/// Bookkeeping of a population besides its individuals; every kind of
+/// population only fills in what it keeps.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PopulationState<I> {
+    /// Generations every individual has been around.
+    pub ages: Vec<usize>,
+    /// Age layer of every individual.
+    pub layers: Vec<usize>,
+    pub generation: usize,
+    /// Survivors of the previous generation competing with the current one.
+    pub parents: Vec<I>,
+}
+
+impl<I> Default for PopulationState<I> {
+    fn default() -> Self {
+        Self {
+            ages: vec![],
+            layers: vec![],
+            generation: 0,
+            parents: vec![],
+        }
+    }
+}
+
+impl<I> PopulationState<I> {
+    pub fn map<J>(self, f: impl FnMut(I) -> J) -> PopulationState<J> {
+        PopulationState {
+            ages: self.ages,
+            layers: self.layers,
+            generation: self.generation,
+            parents: self.parents.into_iter().map(f).collect(),
+        }
+    }
+}
+
+/// Score-proportionate selection over a population; individuals scoring
+/// nothing at all still get picked now and then.
+pub(crate) struct RouletteWheel(WeightedIndex<f32>);
+
+impl RouletteWheel {
+    pub(crate) fn new<I, C>(population: &[I]) -> Self
+    where
+        I: Individual<C>,
+    {
+        Self::from_fitness(population.iter().map(|individual| individual.score()))
+    }
+
+    pub(crate) fn from_fitness(fitness: impl IntoIterator<Item = f32>) -> Self {
+        let weights = fitness.into_iter().map(|fitness| fitness.max(0.00001));
+        Self(WeightedIndex::new(weights).expect("empty population"))
+    }
+
+    pub(crate) fn spin(&self, rng: &mut dyn RngCore) -> usize {
+        self.0.sample(rng)
     }
 }
@@ -0,0 +1,378 @@
+use crate::observer::breed;
+use crate::population::RouletteWheel;
+use crate::*;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// How the maximum age grows from one layer to the next, in multiples of
+/// the age gap.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgingScheme {
+    /// 1, 2, 3, 4, 5, ...
+    Linear,
+    /// 1, 2, 3, 5, 8, ...
+    Fibonacci,
+    /// 1, 2, 4, 9, 16, ...
+    #[default]
+    Polynomial,
+    /// 1, 2, 4, 8, 16, ...
+    Exponential,
+}
+
+impl AgingScheme {
+    pub fn factor(&self, layer: usize) -> usize {
+        match self {
+            AgingScheme::Linear => layer + 1,
+            AgingScheme::Fibonacci => {
+                let (mut a, mut b) = (1, 2);
+                for _ in 0..layer {
+                    (a, b) = (b, a + b);
+                }
+                a
+            }
+            AgingScheme::Polynomial if layer < 2 => layer + 1,
+            AgingScheme::Polynomial => layer * layer,
+            AgingScheme::Exponential => 1 << layer,
+        }
+    }
+}
+
+/// Age-layered population (ALPS). Every layer breeds its share of the next
+/// generation from itself and the layer below, and a child is one
+/// generation older than its oldest parent, rising through the layers as
+/// it outgrows their maximum ages. Every `age_gap` generations the bottom
+/// layer starts over from random individuals.
+pub struct AlpsPopulation<I> {
+    population: Vec<I>,
+    ages: Vec<usize>,
+    layers: Vec<usize>,
+    layer_count: usize,
+    age_gap: usize,
+    scheme: AgingScheme,
+    generation: usize,
+    offspring: Vec<I>,
+}
+
+impl<I> AlpsPopulation<I> {
+    pub fn new(individuals: Vec<I>) -> Self {
+        Self {
+            ages: vec![0; individuals.len()],
+            layers: vec![0; individuals.len()],
+            population: individuals,
+            layer_count: 5,
+            age_gap: 10,
+            scheme: AgingScheme::default(),
+            generation: 0,
+            offspring: vec![],
+        }
+    }
+
+    pub fn with_layers(mut self, layer_count: usize, age_gap: usize, scheme: AgingScheme) -> Self {
+        assert!(layer_count > 0 && age_gap > 0);
+
+        self.layer_count = layer_count;
+        self.age_gap = age_gap;
+        self.scheme = scheme;
+        self.layers = self
+            .ages
+            .iter()
+            .map(|&age| self.layer_for(age, 0))
+            .collect();
+        self
+    }
+
+    pub fn ages(&self) -> &[usize] {
+        &self.ages
+    }
+
+    /// The layer of every individual.
+    pub fn layers(&self) -> &[usize] {
+        &self.layers
+    }
+
+    pub fn layer_count(&self) -> usize {
+        self.layer_count
+    }
+
+    /// Oldest age allowed in `layer`; the top layer has no limit.
+    pub fn max_age(&self, layer: usize) -> Option<usize> {
+        (layer + 1 < self.layer_count).then(|| self.age_gap * self.scheme.factor(layer))
+    }
+
+    /// Lowest layer from `layer` up that is not too young for `age`.
+    fn layer_for(&self, age: usize, layer: usize) -> usize {
+        (layer..self.layer_count)
+            .find(|&layer| self.max_age(layer).is_none_or(|max_age| age <= max_age))
+            .unwrap_or(self.layer_count - 1)
+    }
+
+    /// Children bred for every layer; the top one also gets the remainder.
+    fn slots(&self, layer: usize) -> usize {
+        let size = self.population.len();
+        let slots = size / self.layer_count;
+        if layer + 1 == self.layer_count {
+            slots + size % self.layer_count
+        } else {
+            slots
+        }
+    }
+}
+
+impl<I, C> Population<I, C> for AlpsPopulation<I>
+where
+    I: Individual<C>,
+{
+    fn random(size: usize, config: &C, rng: &mut dyn RngCore) -> Self {
+        Self::new((0..size).map(|_| I::random(config, rng)).collect())
+    }
+
+    fn individuals(&self) -> &[I] {
+        &self.population
+    }
+
+    fn individuals_mut(&mut self) -> &mut [I] {
+        &mut self.population
+    }
+
+    fn replace(&mut self, individuals: Vec<I>) {
+        self.ages = vec![0; individuals.len()];
+        self.layers = vec![0; individuals.len()];
+        self.population = individuals;
+    }
+
+    fn snapshot(&self) -> Option<PopulationState<I>> {
+        Some(PopulationState {
+            ages: self.ages.clone(),
+            layers: self.layers.clone(),
+            generation: self.generation,
+            parents: vec![],
+        })
+    }
+
+    fn restore(&mut self, state: PopulationState<I>) {
+        assert_eq!(state.ages.len(), self.population.len());
+        assert_eq!(state.layers.len(), self.population.len());
+        self.ages = state.ages;
+        self.layers = state.layers;
+        self.generation = state.generation;
+    }
+
+    fn select(&self, _config: &C, rng: &mut dyn RngCore) -> &I {
+        &self.population[RouletteWheel::new(&self.population).spin(rng)]
+    }
+
+    fn evolve_observed(
+        &mut self,
+        params: &C,
+        rng: &mut dyn RngCore,
+        observers: &mut Observers<'_, I>,
+    ) -> PopulationStatistics {
+        let inject = (self.generation + 1).is_multiple_of(self.age_gap);
+        let mut ages = Vec::with_capacity(self.population.len());
+        let mut layers = Vec::with_capacity(self.population.len());
+        let mut parents = vec![];
+        let mut children = vec![];
+        self.offspring.clear();
+
+        for layer in 0..self.layer_count {
+            let slots = self.slots(layer);
+            if layer == 0 && inject {
+                self.offspring
+                    .extend((0..slots).map(|_| I::random(params, rng)));
+                ages.extend(std::iter::repeat_n(0, slots));
+                layers.extend(std::iter::repeat_n(0, slots));
+                continue;
+            }
+
+            // The same or younger layers, down to everyone if those are empty.
+            let mut pool: Vec<usize> = (0..self.population.len())
+                .filter(|&i| self.layers[i] + 1 >= layer && self.layers[i] <= layer)
+                .collect();
+            if pool.is_empty() {
+                pool = (0..self.population.len())
+                    .filter(|&i| self.layers[i] <= layer)
+                    .collect();
+            }
+            if pool.is_empty() {
+                pool = (0..self.population.len()).collect();
+            }
+
+            let wheel =
+                RouletteWheel::from_fitness(pool.iter().map(|&i| self.population[i].score()));
+            let selected = breed(
+                &self.population,
+                slots,
+                params,
+                rng,
+                observers,
+                |rng| pool[wheel.spin(rng)],
+                &mut children,
+            );
+
+            for pair in selected.chunks(2) {
+                let age = self.ages[pair[0]].max(self.ages[pair[1]]) + 1;
+                ages.push(age);
+                layers.push(self.layer_for(age, layer));
+            }
+            parents.extend(selected);
+            self.offspring.append(&mut children);
+        }
+
+        let stats = PopulationStatistics::new(&self.population)
+            .with_selection(SelectionStatistics::new(&self.population, &parents));
+        observers.on_generation_end(&self.population, &stats);
+
+        std::mem::swap(&mut self.population, &mut self.offspring);
+        self.offspring.clear();
+        self.ages = ages;
+        self.layers = layers;
+        self.generation += 1;
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::cell::RefCell;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Value(f32);
+
+    impl Individual<()> for Value {
+        fn random(_params: &(), rng: &mut dyn RngCore) -> Self {
+            Value(rng.gen())
+        }
+
+        fn fitness(&self) -> f32 {
+            self.0
+        }
+
+        fn mutate(self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            self
+        }
+
+        fn crossover(&self, other: &Self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            Value((self.0 + other.0) / 2.0)
+        }
+    }
+
+    /// Layers of the parents picked for every layer in turn.
+    struct ParentLayers<'a> {
+        layers: &'a RefCell<Vec<usize>>,
+        picked: &'a RefCell<Vec<Vec<usize>>>,
+    }
+
+    impl EvolutionObserver<Value> for ParentLayers<'_> {
+        fn after_selection(&mut self, _population: &[Value], parents: &mut Vec<(usize, usize)>) {
+            let layers = self.layers.borrow();
+            self.picked.borrow_mut().push(
+                parents
+                    .iter()
+                    .flat_map(|&(a, b)| [layers[a], layers[b]])
+                    .collect(),
+            );
+        }
+    }
+
+    #[test]
+    fn max_ages() {
+        let max_ages = |scheme| {
+            let population = AlpsPopulation::new(vec![Value(1.0)]).with_layers(6, 3, scheme);
+            (0..6)
+                .map(|layer| population.max_age(layer))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(AlpsPopulation::new(vec![Value(1.0)]).max_age(4), None);
+        assert_eq!(
+            max_ages(AgingScheme::Fibonacci),
+            [Some(3), Some(6), Some(9), Some(15), Some(24), None]
+        );
+        assert_eq!(
+            max_ages(AgingScheme::Polynomial),
+            [Some(3), Some(6), Some(12), Some(27), Some(48), None]
+        );
+        assert_eq!(
+            max_ages(AgingScheme::Exponential)[..3],
+            [Some(3), Some(6), Some(12)]
+        );
+    }
+
+    #[test]
+    fn resumes_from_snapshot() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut population = AlpsPopulation::<Value>::random(30, &(), &mut rng).with_layers(
+            3,
+            2,
+            AgingScheme::Linear,
+        );
+        for _ in 0..5 {
+            population.evolve(&(), &mut rng);
+        }
+
+        let mut resumed = AlpsPopulation::new(population.population.clone()).with_layers(
+            3,
+            2,
+            AgingScheme::Linear,
+        );
+        resumed.restore(population.snapshot().unwrap());
+        let mut resumed_rng = rng.clone();
+        for _ in 0..5 {
+            population.evolve(&(), &mut rng);
+            resumed.evolve(&(), &mut resumed_rng);
+        }
+
+        assert_eq!(resumed.population, population.population);
+        assert_eq!(resumed.ages(), population.ages());
+        assert_eq!(resumed.layers(), population.layers());
+    }
+
+    #[test]
+    fn ages_and_layers() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut population = AlpsPopulation::<Value>::random(30, &(), &mut rng).with_layers(
+            3,
+            2,
+            AgingScheme::Linear,
+        );
+        let layers = RefCell::new(vec![]);
+        let picked = RefCell::new(vec![]);
+        let mut observers = Observers::new().with(ParentLayers {
+            layers: &layers,
+            picked: &picked,
+        });
+
+        for generation in 1..=12 {
+            *layers.borrow_mut() = population.layers().to_vec();
+            population.evolve_observed(&(), &mut rng, &mut observers);
+
+            assert_eq!(population.population.len(), 30);
+            for (&age, &layer) in population.ages().iter().zip(population.layers()) {
+                assert!(population
+                    .max_age(layer)
+                    .is_none_or(|max_age| age <= max_age));
+            }
+            if generation % 2 == 0 {
+                assert_eq!(population.ages()[..10], [0; 10]);
+            }
+        }
+        assert!(population.layers().contains(&2));
+
+        // Parents always come from the same or younger layers; the bottom
+        // layer is not bred on injection generations.
+        drop(observers);
+        let mut expected = (1..=12).flat_map(|generation| {
+            let bottom = (generation % 2 != 0).then_some(0);
+            bottom.into_iter().chain([1, 2])
+        });
+        for picked in picked.into_inner() {
+            let layer = expected.next().unwrap();
+            assert!(picked.iter().all(|&parent| parent <= layer));
+        }
+        assert_eq!(expected.next(), None);
+    }
+}
@@ -0,0 +1,284 @@
+use crate::observer::breed;
+use crate::*;
+use rand::{Rng, RngCore};
+use std::sync::Mutex;
+
+/// NSGA-II: parents are chosen by crowded binary tournament, and the
+/// previous parents compete with the current generation for survival so the
+/// Pareto front found so far is never lost.
+pub struct Nsga2Population<I> {
+    population: Vec<I>,
+    parents: Vec<I>,
+    offspring: Vec<I>,
+    /// Rankings of `population` for `select`, along with the objectives
+    /// they were ranked by, so they are only sorted again once those change.
+    rankings: Mutex<Option<Ranked>>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Ranking {
+    rank: usize,
+    crowding: f32,
+}
+
+/// Objectives of a population and the rankings they give.
+type Ranked = (Vec<Vec<f32>>, Vec<Ranking>);
+
+impl<I> Nsga2Population<I> {
+    pub fn new(individuals: Vec<I>) -> Self {
+        Self {
+            population: individuals,
+            parents: vec![],
+            offspring: vec![],
+            rankings: Mutex::new(None),
+        }
+    }
+
+    pub fn parents(&self) -> &[I] {
+        &self.parents
+    }
+
+    pub fn pareto_front<C>(&self) -> Vec<&I>
+    where
+        I: MultiObjectiveIndividual<C>,
+    {
+        let objectives: Vec<_> = self.population.iter().map(I::objectives).collect();
+
+        non_dominated_sort(&objectives)
+            .first()
+            .map(|front| front.iter().map(|&i| &self.population[i]).collect())
+            .unwrap_or_default()
+    }
+
+    fn rank(objectives: &[Vec<f32>]) -> Vec<Ranking> {
+        let mut rankings = vec![
+            Ranking {
+                rank: 0,
+                crowding: 0.0,
+            };
+            objectives.len()
+        ];
+
+        for (rank, front) in non_dominated_sort(objectives).iter().enumerate() {
+            let distances = crowding_distance(objectives, front);
+            for (&i, crowding) in front.iter().zip(distances) {
+                rankings[i] = Ranking { rank, crowding };
+            }
+        }
+
+        rankings
+    }
+
+    fn survivors<C>(pool: Vec<I>, size: usize) -> Vec<I>
+    where
+        I: MultiObjectiveIndividual<C> + Clone,
+    {
+        let objectives: Vec<_> = pool.iter().map(I::objectives).collect();
+        let mut survivors = Vec::with_capacity(size);
+
+        for front in non_dominated_sort(&objectives) {
+            if survivors.len() + front.len() <= size {
+                survivors.extend(front.iter().map(|&i| pool[i].clone()));
+                continue;
+            }
+
+            let distances = crowding_distance(&objectives, &front);
+            let mut order: Vec<usize> = (0..front.len()).collect();
+            order.sort_by(|&a, &b| distances[b].total_cmp(&distances[a]));
+            survivors.extend(
+                order
+                    .into_iter()
+                    .take(size - survivors.len())
+                    .map(|i| pool[front[i]].clone()),
+            );
+            break;
+        }
+
+        survivors
+    }
+
+    /// Index of the winner of a crowded binary tournament.
+    fn tournament(rankings: &[Ranking], rng: &mut dyn RngCore) -> usize {
+        assert!(!rankings.is_empty(), "empty population");
+
+        let a = rng.gen_range(0..rankings.len());
+        let b = rng.gen_range(0..rankings.len());
+
+        let (ra, rb) = (rankings[a], rankings[b]);
+        if ra.rank != rb.rank {
+            if ra.rank < rb.rank {
+                a
+            } else {
+                b
+            }
+        } else if ra.crowding >= rb.crowding {
+            a
+        } else {
+            b
+        }
+    }
+}
+
+impl<I, C> Population<I, C> for Nsga2Population<I>
+where
+    I: MultiObjectiveIndividual<C> + Clone,
+{
+    fn random(size: usize, config: &C, rng: &mut dyn RngCore) -> Self {
+        Self::new((0..size).map(|_x| I::random(config, rng)).collect())
+    }
+
+    fn individuals(&self) -> &[I] {
+        &self.population
+    }
+
+    fn individuals_mut(&mut self) -> &mut [I] {
+        &mut self.population
+    }
+
+    fn replace(&mut self, individuals: Vec<I>) {
+        self.population = individuals;
+        self.parents.clear();
+    }
+
+    fn snapshot(&self) -> Option<PopulationState<I>> {
+        Some(PopulationState {
+            parents: self.parents.clone(),
+            ..PopulationState::default()
+        })
+    }
+
+    fn restore(&mut self, state: PopulationState<I>) {
+        self.parents = state.parents;
+    }
+
+    fn select(&self, _config: &C, rng: &mut dyn RngCore) -> &I {
+        let objectives: Vec<_> = self.population.iter().map(I::objectives).collect();
+        let mut cache = self.rankings.lock().unwrap();
+        let (_, rankings) = match cache.take() {
+            Some((ranked, rankings)) if ranked == objectives => cache.insert((ranked, rankings)),
+            _ => {
+                let rankings = Self::rank(&objectives);
+                cache.insert((objectives, rankings))
+            }
+        };
+
+        &self.population[Self::tournament(rankings, rng)]
+    }
+
+    fn evolve_observed(
+        &mut self,
+        params: &C,
+        rng: &mut dyn RngCore,
+        observers: &mut Observers<'_, I>,
+    ) -> PopulationStatistics {
+        let size = self.population.len();
+        let pool: Vec<I> = self
+            .parents
+            .iter()
+            .chain(&self.population)
+            .cloned()
+            .collect();
+        let parents = Self::survivors(pool, size);
+
+        let objectives: Vec<_> = parents.iter().map(I::objectives).collect();
+        let rankings = Self::rank(&objectives);
+
+        let selected = breed(
+            &parents,
+            size,
+            params,
+            rng,
+            observers,
+            |rng| Self::tournament(&rankings, rng),
+            &mut self.offspring,
+        );
+
+        let stats = PopulationStatistics::new(&self.population)
+            .with_pareto_front(self.pareto_front().into_iter().map(I::objectives).collect())
+            .with_selection(SelectionStatistics::new(&parents, &selected));
+        observers.on_generation_end(&self.population, &stats);
+
+        std::mem::swap(&mut self.population, &mut self.offspring);
+        self.offspring.clear();
+        self.parents = parents;
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Point(f32, f32);
+
+    impl Individual<()> for Point {
+        fn random(_params: &(), _rng: &mut dyn RngCore) -> Self {
+            Point(0.0, 0.0)
+        }
+
+        fn fitness(&self) -> f32 {
+            self.0 + self.1
+        }
+
+        /// Offspring are always worse than their parents.
+        fn mutate(self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            Point(self.0 - 1.0, self.1 - 1.0)
+        }
+
+        fn crossover(&self, _other: &Self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            self.clone()
+        }
+    }
+
+    impl MultiObjectiveIndividual<()> for Point {
+        fn objectives(&self) -> Vec<f32> {
+            vec![self.0, self.1]
+        }
+    }
+
+    #[test]
+    fn select_ranks_the_current_population() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut population = Nsga2Population::new(vec![Point(0.0, 0.0), Point(1.0, 1.0)]);
+        let mut wins = |population: &Nsga2Population<Point>, winner: &Point| {
+            (0..100)
+                .filter(|_| Population::select(population, &(), &mut rng) == winner)
+                .count()
+        };
+
+        // The dominated point only wins a tournament against itself.
+        assert!(wins(&population, &Point(1.0, 1.0)) > 60);
+        population.replace(vec![Point(2.0, 2.0), Point(0.0, 0.0)]);
+        assert!(wins(&population, &Point(2.0, 2.0)) > 60);
+
+        // Ranked again once the objectives change in place.
+        population.individuals_mut()[1] = Point(3.0, 3.0);
+        assert!(wins(&population, &Point(3.0, 3.0)) > 60);
+    }
+
+    #[test]
+    fn the_pareto_front_survives_worse_offspring() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let front = [Point(2.0, 0.0), Point(0.0, 2.0), Point(1.0, 1.0)];
+        let mut population =
+            Nsga2Population::new(front.iter().cloned().chain([Point(0.0, 0.0)]).collect());
+
+        let stats = population.evolve(&(), &mut rng);
+        assert_eq!(stats.pareto_front().unwrap().len(), 3);
+        assert!(population
+            .population
+            .iter()
+            .all(|child| !front.contains(child)));
+
+        // Every child is dominated by its parent, so the previous parents'
+        // front survives the children it bred.
+        population.evolve(&(), &mut rng);
+        assert_eq!(population.parents().len(), 4);
+        assert!(front
+            .iter()
+            .all(|point| population.parents().contains(point)));
+    }
+}
@@ -1,51 +1,122 @@
+use crate::observer::breed;
+use crate::population::RouletteWheel;
 use crate::*;
-use rand::seq::SliceRandom;
 use rand::RngCore;
 
-pub struct RouletteWheelPopulation<I, C>
-where
-    I: Individual<C>,
-    C: Clone,
-{
+pub struct RouletteWheelPopulation<I> {
     pub population: Vec<I>,
-    config: C,
+    offspring: Vec<I>,
 }
 
-impl<I, C> Population<I, C> for RouletteWheelPopulation<I, C>
+impl<I> RouletteWheelPopulation<I> {
+    pub fn new(individuals: Vec<I>) -> Self {
+        Self {
+            population: individuals,
+            offspring: vec![],
+        }
+    }
+}
+
+impl<I, C> Population<I, C> for RouletteWheelPopulation<I>
 where
     I: Individual<C>,
-    C: Clone,
 {
-    fn new(individuals: Vec<I>, config: &C) -> Box<Self> {
-        Box::new(Self {
-            population: Vec::from(individuals),
-            config: config.clone(),
-        })
+    fn random(size: usize, config: &C, rng: &mut dyn RngCore) -> Self {
+        Self::new((0..size).map(|_x| I::random(config, rng)).collect())
     }
 
-    fn get_config(&self) -> &C {
-        &self.config
+    fn individuals(&self) -> &[I] {
+        &self.population
     }
 
-    fn size(&self) -> usize {
-        self.population.len()
+    fn individuals_mut(&mut self) -> &mut [I] {
+        &mut self.population
     }
 
-    fn get_population(&self) -> &Vec<I> {
-        &self.population
+    fn replace(&mut self, individuals: Vec<I>) {
+        self.population = individuals;
     }
 
-    fn random(size: usize, config: &C, rng: &mut dyn RngCore) -> Self {
-        let population: Vec<I> = (0..size).map(|_x| I::random(config, rng)).collect();
-        Self {
+    fn snapshot(&self) -> Option<PopulationState<I>> {
+        Some(PopulationState::default())
+    }
+
+    fn select(&self, _config: &C, rng: &mut dyn RngCore) -> &I {
+        &self.population[RouletteWheel::new(&self.population).spin(rng)]
+    }
+
+    fn evolve_observed(
+        &mut self,
+        params: &C,
+        rng: &mut dyn RngCore,
+        observers: &mut Observers<'_, I>,
+    ) -> PopulationStatistics {
+        let population = &self.population;
+        let wheel = RouletteWheel::new(population);
+        let parents = breed(
             population,
-            config: config.clone(),
+            population.len(),
+            params,
+            rng,
+            observers,
+            |rng| wheel.spin(rng),
+            &mut self.offspring,
+        );
+
+        let stats = PopulationStatistics::new(population)
+            .with_selection(SelectionStatistics::new(population, &parents));
+        observers.on_generation_end(population, &stats);
+
+        std::mem::swap(&mut self.population, &mut self.offspring);
+        self.offspring.clear();
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// Raw fitness and a selection score that disagree.
+    #[derive(Clone, Debug)]
+    struct Scored(f32, f32);
+
+    impl Individual<()> for Scored {
+        fn random(_params: &(), _rng: &mut dyn RngCore) -> Self {
+            Scored(0.0, 0.0)
+        }
+
+        fn fitness(&self) -> f32 {
+            self.0
+        }
+
+        fn score(&self) -> f32 {
+            self.1
+        }
+
+        fn mutate(self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            self
+        }
+
+        fn crossover(&self, _other: &Self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            self.clone()
         }
     }
 
-    fn select(&self, config: &C, rng: &mut dyn RngCore) -> &I {
-        self.population
-            .choose_weighted(rng, |individual| individual.fitness().max(0.00001))
-            .expect("empty population")
+    #[test]
+    fn selects_by_score_and_reports_fitness() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut population = RouletteWheelPopulation::new(vec![
+            Scored(10.0, 0.0),
+            Scored(20.0, 0.0),
+            Scored(1.0, 1.0),
+        ]);
+
+        let stats = population.evolve(&(), &mut rng);
+        assert_eq!(stats.max_fitness(), 20.0);
+        assert_eq!(stats.min_fitness(), 1.0);
+        assert!(population.population.iter().all(|child| child.0 == 1.0));
     }
 }
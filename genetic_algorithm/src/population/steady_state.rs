@@ -0,0 +1,265 @@
+use crate::observer::breed;
+use crate::population::RouletteWheel;
+use crate::*;
+use rand::seq::{index, SliceRandom};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// Which individuals make room for the offspring of a steady-state
+/// generation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplacementPolicy {
+    #[default]
+    Worst,
+    Oldest,
+    Random,
+    /// The loser of a binary tournament, repeated for every replacement.
+    TournamentLoser,
+}
+
+impl ReplacementPolicy {
+    /// Indices of `count` distinct members of `population` to replace;
+    /// `ages` gives the generations each one has survived.
+    pub fn victims<I, C>(
+        &self,
+        population: &[I],
+        ages: &[usize],
+        count: usize,
+        rng: &mut dyn RngCore,
+    ) -> Vec<usize>
+    where
+        I: Individual<C>,
+    {
+        assert_eq!(population.len(), ages.len());
+
+        let count = count.min(population.len());
+        let mut indices: Vec<usize> = (0..population.len()).collect();
+
+        match self {
+            ReplacementPolicy::Worst => {
+                indices.sort_by(|&a, &b| population[a].score().total_cmp(&population[b].score()));
+            }
+            ReplacementPolicy::Oldest => {
+                indices.sort_by(|&a, &b| ages[b].cmp(&ages[a]));
+            }
+            ReplacementPolicy::Random => {
+                return index::sample(rng, population.len(), count).into_vec();
+            }
+            ReplacementPolicy::TournamentLoser => {
+                let mut victims = Vec::with_capacity(count);
+
+                for _ in 0..count {
+                    let contestants: Vec<usize> = indices
+                        .choose_multiple(rng, 2.min(indices.len()))
+                        .copied()
+                        .collect();
+                    let loser = contestants
+                        .into_iter()
+                        .min_by(|&a, &b| population[a].score().total_cmp(&population[b].score()))
+                        .unwrap();
+
+                    indices.retain(|&i| i != loser);
+                    victims.push(loser);
+                }
+
+                return victims;
+            }
+        }
+
+        indices.truncate(count);
+        indices
+    }
+}
+
+/// Replaces `replacements` individuals per generation, chosen by `policy`,
+/// while everyone else survives; a gap of the whole population is the
+/// usual generational replacement. Parents are picked fitness-proportionally.
+pub struct SteadyStatePopulation<I> {
+    pub population: Vec<I>,
+    ages: Vec<usize>,
+    replacements: usize,
+    policy: ReplacementPolicy,
+    offspring: Vec<I>,
+}
+
+impl<I> SteadyStatePopulation<I> {
+    pub fn new(individuals: Vec<I>) -> Self {
+        Self {
+            ages: vec![0; individuals.len()],
+            population: individuals,
+            replacements: 1,
+            policy: ReplacementPolicy::default(),
+            offspring: vec![],
+        }
+    }
+
+    pub fn with_replacement(mut self, replacements: usize, policy: ReplacementPolicy) -> Self {
+        self.replacements = replacements;
+        self.policy = policy;
+        self
+    }
+
+    /// Generations each individual has survived.
+    pub fn ages(&self) -> &[usize] {
+        &self.ages
+    }
+
+    pub fn replacements(&self) -> usize {
+        self.replacements
+    }
+
+    pub fn policy(&self) -> ReplacementPolicy {
+        self.policy
+    }
+}
+
+impl<I, C> Population<I, C> for SteadyStatePopulation<I>
+where
+    I: Individual<C>,
+{
+    fn random(size: usize, config: &C, rng: &mut dyn RngCore) -> Self {
+        Self::new((0..size).map(|_| I::random(config, rng)).collect())
+    }
+
+    fn individuals(&self) -> &[I] {
+        &self.population
+    }
+
+    fn individuals_mut(&mut self) -> &mut [I] {
+        &mut self.population
+    }
+
+    fn replace(&mut self, individuals: Vec<I>) {
+        self.ages = vec![0; individuals.len()];
+        self.population = individuals;
+    }
+
+    fn snapshot(&self) -> Option<PopulationState<I>> {
+        Some(PopulationState {
+            ages: self.ages.clone(),
+            ..PopulationState::default()
+        })
+    }
+
+    fn restore(&mut self, state: PopulationState<I>) {
+        assert_eq!(state.ages.len(), self.population.len());
+        self.ages = state.ages;
+    }
+
+    fn select(&self, _config: &C, rng: &mut dyn RngCore) -> &I {
+        &self.population[RouletteWheel::new(&self.population).spin(rng)]
+    }
+
+    fn evolve_observed(
+        &mut self,
+        params: &C,
+        rng: &mut dyn RngCore,
+        observers: &mut Observers<'_, I>,
+    ) -> PopulationStatistics {
+        let count = self.replacements.min(self.population.len());
+        let wheel = RouletteWheel::new(&self.population);
+        let parents = breed(
+            &self.population,
+            count,
+            params,
+            rng,
+            observers,
+            |rng| wheel.spin(rng),
+            &mut self.offspring,
+        );
+
+        let stats = PopulationStatistics::new(&self.population)
+            .with_selection(SelectionStatistics::new(&self.population, &parents));
+        observers.on_generation_end(&self.population, &stats);
+
+        let victims = self
+            .policy
+            .victims(&self.population, &self.ages, count, rng);
+        for age in &mut self.ages {
+            *age += 1;
+        }
+        for (victim, child) in victims.into_iter().zip(self.offspring.drain(..)) {
+            self.population[victim] = child;
+            self.ages[victim] = 0;
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Value(f32);
+
+    impl Individual<()> for Value {
+        fn random(_params: &(), _rng: &mut dyn RngCore) -> Self {
+            Value(1.0)
+        }
+
+        fn fitness(&self) -> f32 {
+            self.0
+        }
+
+        fn mutate(self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            self
+        }
+
+        fn crossover(&self, other: &Self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            Value((self.0 + other.0) / 2.0 + 10.0)
+        }
+    }
+
+    fn population() -> Vec<Value> {
+        [3.0, 1.0, 4.0, 0.5, 5.0].map(Value).into()
+    }
+
+    #[test]
+    fn victims() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let population = population();
+        let ages = [0, 4, 2, 1, 3];
+
+        assert_eq!(
+            ReplacementPolicy::Worst.victims(&population, &ages, 2, &mut rng),
+            [3, 1]
+        );
+        assert_eq!(
+            ReplacementPolicy::Oldest.victims(&population, &ages, 2, &mut rng),
+            [1, 4]
+        );
+
+        for policy in [
+            ReplacementPolicy::Random,
+            ReplacementPolicy::TournamentLoser,
+        ] {
+            let mut victims = policy.victims(&population, &ages, 4, &mut rng);
+            victims.sort();
+            victims.dedup();
+            assert_eq!(victims.len(), 4);
+            assert!(policy != ReplacementPolicy::TournamentLoser || !victims.contains(&4));
+        }
+    }
+
+    #[test]
+    fn replaces_only_the_gap() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut population =
+            SteadyStatePopulation::new(population()).with_replacement(2, ReplacementPolicy::Worst);
+
+        population.evolve(&(), &mut rng);
+
+        for (i, individual) in population.population.iter().enumerate() {
+            match i {
+                1 | 3 => assert!(individual.0 > 10.0),
+                _ => assert_eq!(individual, &self::population()[i]),
+            }
+        }
+        assert_eq!(population.ages(), &[1, 0, 1, 0, 1]);
+    }
+}
@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+
+/// A step of the fitness pipeline run before selection; see
+/// `apply_fitness_transforms`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FitnessTransform {
+    /// Goldberg's linear scaling: the mean is kept and the best becomes
+    /// `multiplier` times the mean, unless that would push the worst below
+    /// zero, in which case the worst is mapped to zero instead. Fitness is
+    /// left as it is unless `multiplier` exceeds 1.
+    Linear { multiplier: f32 },
+    /// Subtracts `mean - c * std_dev`, clamping at zero.
+    SigmaTruncation { c: f32 },
+    /// Subtracts the worst fitness.
+    Windowing,
+    /// Raises fitness, clamped at zero, to `exponent`.
+    Power { exponent: f32 },
+    /// Replaces fitness with its rank, `1` for the worst; ties share the
+    /// average of their ranks.
+    Rank,
+    /// Subtracts fitness from the best, turning minimization into
+    /// maximization.
+    Inversion,
+}
+
+impl FitnessTransform {
+    pub fn apply(&self, fitnesses: &mut [f32]) {
+        if fitnesses.is_empty() {
+            return;
+        }
+
+        let n = fitnesses.len() as f32;
+        let min = fitnesses.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = fitnesses.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let mean = fitnesses.iter().sum::<f32>() / n;
+
+        match *self {
+            FitnessTransform::Linear { multiplier } => {
+                if max <= mean || multiplier <= 1.0 {
+                    return;
+                }
+
+                let (a, b) = if min > (multiplier * mean - max) / (multiplier - 1.0) {
+                    let a = (multiplier - 1.0) * mean / (max - mean);
+                    (a, mean * (1.0 - a))
+                } else {
+                    let a = mean / (mean - min);
+                    (a, -min * a)
+                };
+
+                for fitness in fitnesses {
+                    *fitness = a * *fitness + b;
+                }
+            }
+            FitnessTransform::SigmaTruncation { c } => {
+                let std_dev =
+                    (fitnesses.iter().map(|f| (f - mean).powi(2)).sum::<f32>() / n).sqrt();
+                let offset = mean - c * std_dev;
+
+                for fitness in fitnesses {
+                    *fitness = (*fitness - offset).max(0.0);
+                }
+            }
+            FitnessTransform::Windowing => {
+                for fitness in fitnesses {
+                    *fitness -= min;
+                }
+            }
+            FitnessTransform::Power { exponent } => {
+                for fitness in fitnesses {
+                    *fitness = fitness.max(0.0).powf(exponent);
+                }
+            }
+            FitnessTransform::Rank => {
+                let mut order: Vec<usize> = (0..fitnesses.len()).collect();
+                order.sort_by(|&a, &b| fitnesses[a].total_cmp(&fitnesses[b]));
+
+                let mut ranks = vec![0.0; fitnesses.len()];
+                let mut start = 0;
+                while start < order.len() {
+                    let mut end = start + 1;
+                    while end < order.len() && fitnesses[order[end]] == fitnesses[order[start]] {
+                        end += 1;
+                    }
+
+                    let rank = (start + end + 1) as f32 / 2.0;
+                    for &i in &order[start..end] {
+                        ranks[i] = rank;
+                    }
+                    start = end;
+                }
+
+                fitnesses.copy_from_slice(&ranks);
+            }
+            FitnessTransform::Inversion => {
+                for fitness in fitnesses {
+                    *fitness = max - *fitness;
+                }
+            }
+        }
+    }
+}
+
+/// Runs `transforms` over the population's fitnesses in order.
+pub fn apply_fitness_transforms(transforms: &[FitnessTransform], fitnesses: &mut [f32]) {
+    for transform in transforms {
+        transform.apply(fitnesses);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn transformed(transforms: &[FitnessTransform], fitnesses: &[f32]) -> Vec<f32> {
+        let mut fitnesses = fitnesses.to_vec();
+        apply_fitness_transforms(transforms, &mut fitnesses);
+        fitnesses
+    }
+
+    #[test]
+    fn linear() {
+        let scaled = transformed(
+            &[FitnessTransform::Linear { multiplier: 2.0 }],
+            &[4.0, 5.0, 6.0],
+        );
+        assert_relative_eq!(scaled.as_slice(), [0.0, 5.0, 10.0].as_slice());
+
+        let scaled = transformed(
+            &[FitnessTransform::Linear { multiplier: 1.5 }],
+            &[4.0, 5.0, 6.0],
+        );
+        assert_relative_eq!(scaled.as_slice(), [2.5, 5.0, 7.5].as_slice());
+
+        let scaled = transformed(
+            &[FitnessTransform::Linear { multiplier: 1.0 }],
+            &[4.0, 5.0, 6.0],
+        );
+        assert_eq!(scaled, [4.0, 5.0, 6.0]);
+    }
+
+    #[test]
+    fn sigma_truncation_and_windowing() {
+        let truncated = transformed(
+            &[FitnessTransform::SigmaTruncation { c: 0.0 }],
+            &[1.0, 2.0, 6.0],
+        );
+        assert_eq!(truncated, [0.0, 0.0, 3.0]);
+
+        assert_eq!(
+            transformed(&[FitnessTransform::Windowing], &[-1.0, 2.0]),
+            [0.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn rank_power_and_inversion() {
+        assert_eq!(
+            transformed(&[FitnessTransform::Rank], &[0.5, -3.0, 0.5, 9.0]),
+            [2.5, 1.0, 2.5, 4.0]
+        );
+        assert_eq!(
+            transformed(
+                &[
+                    FitnessTransform::Inversion,
+                    FitnessTransform::Power { exponent: 2.0 }
+                ],
+                &[1.0, 3.0, 4.0]
+            ),
+            [9.0, 1.0, 0.0]
+        );
+    }
+}
@@ -1,16 +1,25 @@
 use crate::*;
 use std::cmp::Ordering;
+use std::collections::BTreeSet;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct PopulationStatistics {
     min_fitness: f32,
     max_fitness: f32,
     avg_fitness: f32,
     median_fitness: f32,
+    std_dev_fitness: f32,
+    diversity: Option<f32>,
+    fitnesses: Vec<f32>,
+    percentiles: Vec<(f32, f32)>,
+    histogram: Option<Histogram>,
+    selection: Option<SelectionStatistics>,
+    pareto_front: Option<Vec<Vec<f32>>>,
+    mutation_rates: Option<MutationRates>,
 }
 
 impl PopulationStatistics {
-    pub(crate) fn new<I, C>(population: &[I]) -> Self
+    pub fn new<I, C>(population: &[I]) -> Self
     where
         I: Individual<C>,
     {
@@ -28,20 +37,65 @@ impl PopulationStatistics {
         let max_fitness = fitnesses[len - 1];
         let avg_fitness = fitnesses.iter().sum::<f32>() / (len as f32);
 
-        let median_fitness = if len % 2 == 0 {
+        let median_fitness = if len.is_multiple_of(2) {
             (fitnesses[len / 2 - 1] + fitnesses[len / 2]) / 2.0
         } else {
             fitnesses[len / 2]
         };
 
+        let std_dev_fitness = (fitnesses
+            .iter()
+            .map(|fitness| (fitness - avg_fitness).powi(2))
+            .sum::<f32>()
+            / (len as f32))
+            .sqrt();
+
         Self {
             min_fitness,
             max_fitness,
             avg_fitness,
             median_fitness,
+            std_dev_fitness,
+            diversity: diversity(population),
+            fitnesses,
+            percentiles: vec![],
+            histogram: None,
+            selection: None,
+            pareto_front: None,
+            mutation_rates: None,
         }
     }
 
+    /// Records the fitness at each of `percentiles`, given as fractions
+    /// in `0..=1`.
+    pub fn with_percentiles(mut self, percentiles: &[f32]) -> Self {
+        self.percentiles = percentiles
+            .iter()
+            .map(|&p| (p, self.percentile(p)))
+            .collect();
+        self
+    }
+
+    pub fn with_histogram(mut self, bins: usize) -> Self {
+        self.histogram = (bins > 0).then(|| Histogram::new(&self.fitnesses, bins));
+        self
+    }
+
+    pub fn with_selection(mut self, selection: SelectionStatistics) -> Self {
+        self.selection = Some(selection);
+        self
+    }
+
+    pub(crate) fn with_pareto_front(mut self, pareto_front: Vec<Vec<f32>>) -> Self {
+        self.pareto_front = Some(pareto_front);
+        self
+    }
+
+    pub fn with_mutation_rates(mut self, mutation_rates: MutationRates) -> Self {
+        self.mutation_rates = Some(mutation_rates);
+        self
+    }
+
     pub fn min_fitness(&self) -> f32 {
         self.min_fitness
     }
@@ -57,4 +111,219 @@ impl PopulationStatistics {
     pub fn median_fitness(&self) -> f32 {
         self.median_fitness
     }
+
+    pub fn std_dev_fitness(&self) -> f32 {
+        self.std_dev_fitness
+    }
+
+    /// Mean pairwise genotype distance, for individuals that implement
+    /// `Individual::distance`.
+    pub fn diversity(&self) -> Option<f32> {
+        self.diversity
+    }
+
+    /// Fitness at fraction `p` of the sorted population, interpolating
+    /// linearly between neighbours.
+    pub fn percentile(&self, p: f32) -> f32 {
+        let position = p.clamp(0.0, 1.0) * (self.fitnesses.len() - 1) as f32;
+        let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+        let t = position - lower as f32;
+
+        self.fitnesses[lower] + (self.fitnesses[upper] - self.fitnesses[lower]) * t
+    }
+
+    /// `(p, fitness)` pairs recorded by `with_percentiles`.
+    pub fn percentiles(&self) -> &[(f32, f32)] {
+        &self.percentiles
+    }
+
+    pub fn histogram(&self) -> Option<&Histogram> {
+        self.histogram.as_ref()
+    }
+
+    pub fn selection(&self) -> Option<&SelectionStatistics> {
+        self.selection.as_ref()
+    }
+
+    /// Objective vectors of the non-dominated individuals, when the
+    /// population is multi-objective.
+    pub fn pareto_front(&self) -> Option<&[Vec<f32>]> {
+        self.pareto_front.as_deref()
+    }
+
+    /// Mutation rates in effect when this generation was bred, if reported.
+    pub fn mutation_rates(&self) -> Option<MutationRates> {
+        self.mutation_rates
+    }
+}
+
+/// Fitness counts over equal-width bins spanning `min..=max`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    min: f32,
+    max: f32,
+    counts: Vec<usize>,
+}
+
+impl Histogram {
+    pub fn new(values: &[f32], bins: usize) -> Self {
+        assert!(bins > 0);
+
+        let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let mut counts = vec![0; bins];
+
+        for value in values {
+            let bin = if max > min {
+                (((value - min) / (max - min)) * bins as f32) as usize
+            } else {
+                0
+            };
+            counts[bin.min(bins - 1)] += 1;
+        }
+
+        Self { min, max, counts }
+    }
+
+    pub fn min(&self) -> f32 {
+        self.min
+    }
+
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+}
+
+/// How strongly one round of parent selection favoured the fit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelectionStatistics {
+    selections: usize,
+    unique_parents: usize,
+    differential: f32,
+    intensity: f32,
+}
+
+impl SelectionStatistics {
+    /// `parents` are indices into `population`, one per selection.
+    pub fn new<I, C>(population: &[I], parents: &[usize]) -> Self
+    where
+        I: Individual<C>,
+    {
+        assert!(!population.is_empty());
+
+        let fitnesses: Vec<_> = population.iter().map(I::fitness).collect();
+        let mean = fitnesses.iter().sum::<f32>() / fitnesses.len() as f32;
+        let std_dev = (fitnesses
+            .iter()
+            .map(|fitness| (fitness - mean).powi(2))
+            .sum::<f32>()
+            / fitnesses.len() as f32)
+            .sqrt();
+
+        let differential = if parents.is_empty() {
+            0.0
+        } else {
+            parents.iter().map(|&i| fitnesses[i]).sum::<f32>() / parents.len() as f32 - mean
+        };
+
+        Self {
+            selections: parents.len(),
+            unique_parents: parents.iter().collect::<BTreeSet<_>>().len(),
+            differential,
+            intensity: if std_dev > 0.0 {
+                differential / std_dev
+            } else {
+                0.0
+            },
+        }
+    }
+
+    pub fn selections(&self) -> usize {
+        self.selections
+    }
+
+    pub fn unique_parents(&self) -> usize {
+        self.unique_parents
+    }
+
+    /// Mean fitness of the selected parents minus that of the population.
+    pub fn differential(&self) -> f32 {
+        self.differential
+    }
+
+    /// The selection differential in population standard deviations.
+    pub fn intensity(&self) -> f32 {
+        self.intensity
+    }
+}
+
+fn diversity<I, C>(population: &[I]) -> Option<f32>
+where
+    I: Individual<C>,
+{
+    let mut sum = 0.0;
+    let mut pairs = 0;
+
+    for (i, a) in population.iter().enumerate() {
+        for b in &population[i + 1..] {
+            sum += a.distance(b)?;
+            pairs += 1;
+        }
+    }
+
+    Some(if pairs == 0 { 0.0 } else { sum / pairs as f32 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::RngCore;
+
+    struct Point(f32);
+
+    impl Individual<()> for Point {
+        fn random(_params: &(), _rng: &mut dyn RngCore) -> Self {
+            Point(0.0)
+        }
+
+        fn fitness(&self) -> f32 {
+            self.0
+        }
+
+        fn mutate(self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            self
+        }
+
+        fn crossover(&self, _other: &Self, _params: &(), _rng: &mut dyn RngCore) -> Self {
+            Point(self.0)
+        }
+
+        fn distance(&self, other: &Self) -> Option<f32> {
+            Some((self.0 - other.0).abs())
+        }
+    }
+
+    #[test]
+    fn statistics() {
+        let population: Vec<_> = [1.0, 2.0, 3.0, 4.0, 5.0].map(Point).into();
+        let stats = PopulationStatistics::new(&population)
+            .with_percentiles(&[0.25, 0.9])
+            .with_histogram(2)
+            .with_selection(SelectionStatistics::new(&population, &[4, 4, 3, 2]));
+
+        assert_relative_eq!(stats.std_dev_fitness(), 2.0f32.sqrt());
+        assert_relative_eq!(stats.diversity().unwrap(), 2.0);
+        assert_eq!(stats.percentiles(), &[(0.25, 2.0), (0.9, 4.6)]);
+        assert_eq!(stats.histogram().unwrap().counts(), &[2, 3]);
+
+        let selection = stats.selection().unwrap();
+        assert_eq!(selection.unique_parents(), 3);
+        assert_relative_eq!(selection.differential(), 1.25);
+        assert_relative_eq!(selection.intensity(), 1.25 / 2.0f32.sqrt());
+    }
 }
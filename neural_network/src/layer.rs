@@ -1,6 +1,5 @@
 use crate::*;
-use ndarray::{Array, Array1, Array2, ShapeBuilder};
-use std::cmp::max;
+use ndarray::{Array1, Array2};
 use std::ops::Add;
 
 #[derive(Copy, Clone, Debug)]
@@ -42,6 +41,34 @@ impl Layer {
         }
     }
 
+    pub fn from_parameters(
+        layer_type: LayerType,
+        input_size: usize,
+        output_size: usize,
+        parameters: &mut impl Iterator<Item = f32>,
+    ) -> Self {
+        let weights = Array2::from_shape_simple_fn((input_size, output_size), || {
+            parameters.next().expect("not enough parameters")
+        });
+        let biases = Array1::from_shape_simple_fn(output_size, || {
+            parameters.next().expect("not enough parameters")
+        });
+        Self {
+            layer_type,
+            weights,
+            biases,
+        }
+    }
+
+    pub fn parameter_count(input_size: usize, output_size: usize) -> usize {
+        (input_size + 1) * output_size
+    }
+
+    /// Weights in row-major order followed by the biases.
+    pub fn parameters(&self) -> impl Iterator<Item = f32> + '_ {
+        self.weights.iter().chain(self.biases.iter()).copied()
+    }
+
     pub fn forward(&self, inputs: Array1<f32>) -> Array1<f32> {
         inputs
             .dot(&self.weights)
@@ -1,7 +1,6 @@
 mod layer;
 mod network;
 
-use self::layer::*;
 use rand::{Rng, RngCore};
 
 pub use self::layer::*;
@@ -1,5 +1,5 @@
 use crate::*;
-use ndarray::{Array1, Array2};
+use ndarray::Array1;
 
 #[derive(Clone, Debug)]
 pub struct Network {
@@ -12,14 +12,37 @@ impl Network {
     }
 
     pub fn random(topology: &[usize], rng: &mut dyn RngCore) -> Self {
-        let mut layers: Vec<Layer> = topology
+        let layers: Vec<Layer> = topology
             .windows(2)
             .map(|layers| Layer::random(LayerType::Calc, layers[0], layers[1], rng))
             .collect();
-        let len = layers.len();
-        layers[0].layer_type = LayerType::Input;
-        layers[len - 1].layer_type = LayerType::Output;
-        Self { layers }
+        Self::with_layer_types(layers)
+    }
+
+    pub fn from_parameters(topology: &[usize], parameters: &[f32]) -> Self {
+        assert_eq!(parameters.len(), Self::parameter_count(topology));
+
+        let mut parameters = parameters.iter().copied();
+        let layers: Vec<Layer> = topology
+            .windows(2)
+            .map(|layers| {
+                Layer::from_parameters(LayerType::Calc, layers[0], layers[1], &mut parameters)
+            })
+            .collect();
+        Self::with_layer_types(layers)
+    }
+
+    pub fn parameter_count(topology: &[usize]) -> usize {
+        topology
+            .windows(2)
+            .map(|layers| Layer::parameter_count(layers[0], layers[1]))
+            .sum()
+    }
+
+    /// Flattens every layer's weights and biases into a single vector, in
+    /// the order expected by `from_parameters`.
+    pub fn parameters(&self) -> Vec<f32> {
+        self.layers.iter().flat_map(Layer::parameters).collect()
     }
 
     pub fn forward(&self, inputs: Array1<f32>) -> Array1<f32> {
@@ -28,3 +51,36 @@ impl Network {
             .fold(inputs, |inputs, layer| layer.forward(inputs))
     }
 }
+
+impl Network {
+    fn with_layer_types(mut layers: Vec<Layer>) -> Self {
+        let len = layers.len();
+        layers[0].layer_type = LayerType::Input;
+        layers[len - 1].layer_type = LayerType::Output;
+        Self { layers }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn parameters_round_trip() {
+        let topology = [4, 3, 2];
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let network = Network::random(&topology, &mut rng);
+
+        let parameters = network.parameters();
+        assert_eq!(parameters.len(), Network::parameter_count(&topology));
+        assert_eq!(parameters.len(), 4 * 3 + 3 + 3 * 2 + 2);
+
+        let restored = Network::from_parameters(&topology, &parameters);
+        assert_eq!(restored.parameters(), parameters);
+
+        let inputs = Array1::from_vec(vec![0.1, 0.2, 0.3, 0.4]);
+        assert_eq!(restored.forward(inputs.clone()), network.forward(inputs));
+    }
+}
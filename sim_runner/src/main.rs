@@ -0,0 +1,306 @@
+mod sweep;
+
+use animal_simulation::*;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+const USAGE: &str = "usage: sim_runner [CONFIG] [key=value]... [--seed N] [--generations N] \
+[--out-dir DIR] [--checkpoint-every N] [--resume CHECKPOINT] [--target-fitness F] \
+[--stagnation N] [--wall-clock SECONDS]
+       sim_runner sweep [OPTIONS]...
+
+--generations is the length of the whole run, so a resumed run only trains the generations
+left. --resume takes the config and seed from the checkpoint and appends to statistics.csv.";
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    /// The config file and overrides, handed to `Config::from_args`.
+    config: Vec<String>,
+    seed: Option<u64>,
+    /// Generations of the whole run, counting those before a resume.
+    generations: usize,
+    out_dir: PathBuf,
+    /// Generations between checkpoints; zero only checkpoints at the end.
+    checkpoint_every: usize,
+    resume: Option<PathBuf>,
+    stop_conditions: Vec<StopCondition>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            config: vec![],
+            seed: None,
+            generations: 100,
+            out_dir: PathBuf::from("runs"),
+            checkpoint_every: 10,
+            resume: None,
+            stop_conditions: vec![],
+        }
+    }
+}
+
+/// Parses the argument following `flag`.
+fn value<T: std::str::FromStr>(
+    flag: &str,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<T, String> {
+    let value = args.next().ok_or(format!("{flag} needs a value"))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {flag}: {value}"))
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => options.seed = Some(value(&arg, &mut args)?),
+            "--generations" => options.generations = value(&arg, &mut args)?,
+            "--out-dir" => options.out_dir = value(&arg, &mut args)?,
+            "--checkpoint-every" => options.checkpoint_every = value(&arg, &mut args)?,
+            "--resume" => options.resume = Some(value(&arg, &mut args)?),
+            "--target-fitness" => options
+                .stop_conditions
+                .push(StopCondition::TargetFitness(value(&arg, &mut args)?)),
+            "--stagnation" => options
+                .stop_conditions
+                .push(StopCondition::Stagnation(value(&arg, &mut args)?)),
+            "--wall-clock" => {
+                options
+                    .stop_conditions
+                    .push(StopCondition::WallClock(Duration::from_secs_f64(value(
+                        &arg, &mut args,
+                    )?)))
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown option {flag}")),
+            _ => options.config.push(arg),
+        }
+    }
+    if options.resume.is_some() && (!options.config.is_empty() || options.seed.is_some()) {
+        return Err("--resume takes the config and seed from the checkpoint".to_string());
+    }
+
+    Ok(options)
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).peekable();
+    if args.next_if_eq("sweep").is_some() {
+        return sweep::main(args);
+    }
+
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let mut simulation = match &options.resume {
+        Some(checkpoint) => Simulation::load_checkpoint(checkpoint)?,
+        None => {
+            let config = Config::from_args(&options.config)?;
+            let seed = options.seed.unwrap_or_else(rand::random);
+            println!("seed {seed}");
+            Simulation::try_from_seed(&config, seed)?
+        }
+    };
+
+    let remaining = options.generations.saturating_sub(simulation.generation);
+    if remaining == 0 {
+        println!("already trained {} generations", simulation.generation);
+        return Ok(());
+    }
+
+    fs::create_dir_all(&options.out_dir)?;
+    let config_file = File::create(options.out_dir.join("config.json"))?;
+    serde_json::to_writer_pretty(config_file, simulation.config())?;
+
+    let mut csv = statistics_csv(
+        &options.out_dir.join("statistics.csv"),
+        options.resume.is_some().then_some(simulation.generation),
+    )?;
+
+    let checkpoint = options.out_dir.join("checkpoint.json");
+    let mut checkpoints = true;
+
+    let mut controller = options.stop_conditions.iter().cloned().fold(
+        RunController::new().stop_when(StopCondition::MaxGenerations(remaining)),
+        RunController::stop_when,
+    );
+    let reason = loop {
+        let statistics = simulation.train();
+        println!("{statistics}\n");
+        write_row(&mut csv, &statistics)?;
+
+        if checkpoints
+            && options.checkpoint_every > 0
+            && simulation
+                .generation
+                .is_multiple_of(options.checkpoint_every)
+        {
+            checkpoints = save_checkpoint(&simulation, &checkpoint);
+        }
+        if let Some(reason) = controller.record(&statistics) {
+            break reason;
+        }
+    };
+    csv.flush()?;
+    if checkpoints {
+        save_checkpoint(&simulation, &checkpoint);
+    }
+
+    if let Some(champion) = simulation.champion() {
+        champion.save(simulation.config(), options.out_dir.join("champion.json"))?;
+        println!(
+            "champion from generation {} with fitness {:.2}",
+            champion.generation, champion.fitness
+        );
+    }
+    println!(
+        "stopped at generation {} ({reason:?}), best fitness {:.2}",
+        simulation.generation,
+        controller.best_fitness()
+    );
+
+    Ok(())
+}
+
+/// Returns whether checkpointing is worth trying again; simulations that
+/// cannot be checkpointed at all are only reported once.
+fn save_checkpoint(simulation: &Simulation, path: &Path) -> bool {
+    match simulation.save_checkpoint(path) {
+        Ok(()) => true,
+        Err(CheckpointError::Unsupported(what)) => {
+            eprintln!("not checkpointing: cannot checkpoint {what}");
+            false
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            true
+        }
+    }
+}
+
+const CSV_HEADER: &str = "generation,min,max,avg,median,std,starved";
+
+/// Opens `path` for the rows to come. Resuming at generation `resume` keeps
+/// the rows of the generations before it, dropping any written after the
+/// checkpoint; otherwise the file starts over.
+fn statistics_csv(path: &Path, resume: Option<usize>) -> io::Result<BufWriter<File>> {
+    let kept = match (resume, fs::read_to_string(path)) {
+        (Some(generation), Ok(text)) => text
+            .lines()
+            .skip(1)
+            .filter(|row| {
+                row.split(',')
+                    .next()
+                    .and_then(|cell| cell.parse::<usize>().ok())
+                    .is_some_and(|row| row < generation)
+            })
+            .map(|row| format!("{row}\n"))
+            .collect(),
+        (Some(_), Err(err)) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+        _ => String::new(),
+    };
+
+    let mut csv = BufWriter::new(File::create(path)?);
+    writeln!(csv, "{CSV_HEADER}")?;
+    csv.write_all(kept.as_bytes())?;
+    Ok(csv)
+}
+
+fn write_row(csv: &mut impl Write, statistics: &Statistics) -> io::Result<()> {
+    let ga = &statistics.ga;
+    writeln!(
+        csv,
+        "{},{},{},{},{},{},{}",
+        statistics.generation,
+        ga.min_fitness(),
+        ga.max_fitness(),
+        ga.avg_fitness(),
+        ga.median_fitness(),
+        ga.std_dev_fitness(),
+        statistics.starved
+    )?;
+    csv.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn args() {
+        let options = parse(&[
+            "config.toml",
+            "--seed",
+            "7",
+            "world_animals=8",
+            "--generations",
+            "5",
+            "--out-dir",
+            "out",
+            "--stagnation",
+            "3",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            options,
+            Options {
+                config: vec!["config.toml".into(), "world_animals=8".into()],
+                seed: Some(7),
+                generations: 5,
+                out_dir: "out".into(),
+                stop_conditions: vec![StopCondition::Stagnation(3)],
+                ..Options::default()
+            }
+        );
+
+        assert!(parse(&["--resume", "checkpoint.json"]).is_ok());
+        assert!(parse(&["--resume", "checkpoint.json", "--seed", "7"]).is_err());
+        assert!(parse(&["--resume", "checkpoint.json", "world_animals=8"]).is_err());
+        assert!(parse(&["--seed"]).is_err());
+        assert!(parse(&["--seed", "x"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+    }
+
+    #[test]
+    fn resumed_statistics_keep_earlier_rows() {
+        let path = std::env::temp_dir().join(format!("sim_runner_{}.csv", std::process::id()));
+        fs::write(&path, format!("{CSV_HEADER}\n0,a\n1,b\n2,c\n3,d\n")).unwrap();
+
+        let mut csv = statistics_csv(&path, Some(2)).unwrap();
+        writeln!(csv, "2,e").unwrap();
+        drop(csv);
+        let resumed = fs::read_to_string(&path).unwrap();
+
+        statistics_csv(&path, None).unwrap();
+        let restarted = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(resumed, format!("{CSV_HEADER}\n0,a\n1,b\n2,e\n"));
+        assert_eq!(restarted, format!("{CSV_HEADER}\n"));
+    }
+}
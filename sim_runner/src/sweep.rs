@@ -0,0 +1,511 @@
+use crate::value;
+use animal_simulation::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde_json::json;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+const USAGE: &str = "usage: sim_runner sweep [CONFIG] [key=value]... [--grid KEY=[V, ...]]... \
+[--random KEY=LOW..HIGH|KEY=[V, ...]]... [--samples N] [--seeds N] [--seed N] \
+[--generations N] [--jobs N] [--out-dir DIR]";
+
+/// Values a swept setting can take.
+#[derive(Clone, Debug, PartialEq)]
+enum Domain {
+    /// Written as a TOML array, e.g. `[5, 9, 13]` or `["cma_es", "one_plus_lambda"]`.
+    Choice(Vec<String>),
+    /// `LOW..HIGH` with integer ends, both included.
+    Integers(i64, i64),
+    /// `LOW..HIGH` otherwise, drawn uniformly.
+    Floats(f32, f32),
+}
+
+impl Domain {
+    fn parse(text: &str) -> Result<Self, String> {
+        if text.starts_with('[') {
+            let mut table: toml::Table = toml::from_str(&format!("values = {text}"))
+                .map_err(|err| format!("invalid list {text}: {err}"))?;
+            let Some(toml::Value::Array(values)) = table.remove("values") else {
+                return Err(format!("invalid list {text}"));
+            };
+            if values.is_empty() {
+                return Err("lists of values must not be empty".to_string());
+            }
+
+            // Strings stay bare, which `Config::set` reads back as strings.
+            let values = values
+                .into_iter()
+                .map(|value| match value {
+                    toml::Value::String(value) => value,
+                    value => value.to_string(),
+                })
+                .collect();
+            return Ok(Domain::Choice(values));
+        }
+
+        let (low, high) = text
+            .split_once("..")
+            .ok_or(format!("expected a list or a LOW..HIGH range, got {text}"))?;
+        let (low, high) = (low.trim(), high.trim());
+        if let (Ok(low), Ok(high)) = (low.parse(), high.parse()) {
+            if low > high {
+                return Err(format!("empty range {text}"));
+            }
+            return Ok(Domain::Integers(low, high));
+        }
+        match (low.parse(), high.parse()) {
+            (Ok(low), Ok(high)) if low < high => Ok(Domain::Floats(low, high)),
+            (Ok(_), Ok(_)) => Err(format!("empty range {text}")),
+            _ => Err(format!("invalid range {text}")),
+        }
+    }
+
+    fn sample(&self, rng: &mut impl Rng) -> String {
+        match self {
+            Domain::Choice(values) => values[rng.gen_range(0..values.len())].clone(),
+            Domain::Integers(low, high) => rng.gen_range(*low..=*high).to_string(),
+            Domain::Floats(low, high) => rng.gen_range(*low..*high).to_string(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    /// The base config file and overrides, handed to `Config::from_args`.
+    config: Vec<String>,
+    /// Every combination of these values is tried.
+    grid: Vec<(String, Vec<String>)>,
+    /// Drawn `samples` times for each combination of the grid.
+    random: Vec<(String, Domain)>,
+    samples: usize,
+    /// Runs per configuration, seeded `seed`, `seed + 1` and so on.
+    seeds: usize,
+    /// Also seeds the random search.
+    seed: u64,
+    generations: usize,
+    /// Runs at a time, each on one thread whatever `sim_threads` says.
+    jobs: usize,
+    out_dir: PathBuf,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            config: vec![],
+            grid: vec![],
+            random: vec![],
+            samples: 10,
+            seeds: 5,
+            seed: 0,
+            generations: 50,
+            jobs: thread::available_parallelism().map_or(1, |jobs| jobs.get()),
+            out_dir: PathBuf::from("sweeps"),
+        }
+    }
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    fn parameter(
+        flag: &str,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<(String, Domain), String> {
+        let arg: String = value(flag, args)?;
+        let (key, domain) = arg
+            .split_once('=')
+            .ok_or(format!("{flag} expects KEY=VALUES, got {arg}"))?;
+        Ok((key.trim().to_string(), Domain::parse(domain.trim())?))
+    }
+
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--grid" => match parameter(&arg, &mut args)? {
+                (key, Domain::Choice(values)) => options.grid.push((key, values)),
+                (key, _) => return Err(format!("--grid needs a list of values for {key}")),
+            },
+            "--random" => options.random.push(parameter(&arg, &mut args)?),
+            "--samples" => options.samples = value(&arg, &mut args)?,
+            "--seeds" => options.seeds = value(&arg, &mut args)?,
+            "--seed" => options.seed = value(&arg, &mut args)?,
+            "--generations" => options.generations = value(&arg, &mut args)?,
+            "--jobs" => options.jobs = value(&arg, &mut args)?,
+            "--out-dir" => options.out_dir = value(&arg, &mut args)?,
+            flag if flag.starts_with("--") => return Err(format!("unknown option {flag}")),
+            _ => options.config.push(arg),
+        }
+    }
+
+    if options.seeds == 0 || options.jobs == 0 || options.generations == 0 {
+        return Err("--seeds, --jobs and --generations must be positive".to_string());
+    }
+    Ok(options)
+}
+
+pub fn main(args: impl Iterator<Item = String>) -> ExitCode {
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match sweep(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+impl Options {
+    fn keys(&self) -> Vec<&str> {
+        let grid = self.grid.iter().map(|(key, _)| key.as_str());
+        let random = self.random.iter().map(|(key, _)| key.as_str());
+        grid.chain(random).collect()
+    }
+
+    /// The swept settings of every configuration, in the order of `keys`.
+    fn settings(&self) -> Vec<Vec<String>> {
+        let mut grid = vec![vec![]];
+        for (_, values) in &self.grid {
+            grid = grid
+                .into_iter()
+                .flat_map(|point: Vec<String>| {
+                    values.iter().map(move |value| {
+                        let mut point = point.clone();
+                        point.push(value.clone());
+                        point
+                    })
+                })
+                .collect();
+        }
+        if self.random.is_empty() {
+            return grid;
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        grid.into_iter()
+            .flat_map(|point| vec![point; self.samples])
+            .map(|mut point| {
+                point.extend(
+                    self.random
+                        .iter()
+                        .map(|(_, domain)| domain.sample(&mut rng)),
+                );
+                point
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct RunResult {
+    seed: u64,
+    /// Max raw fitness of the last generation.
+    final_fitness: f32,
+    /// Fitness of the champion, the fittest brain of the whole run.
+    best_fitness: f32,
+}
+
+fn run(config: &Config, seed: u64, generations: usize) -> Result<RunResult, ConfigError> {
+    let mut simulation = Simulation::try_from_seed(config, seed)?;
+    let mut result = RunResult {
+        seed,
+        final_fitness: 0.0,
+        best_fitness: f32::NEG_INFINITY,
+    };
+
+    for _ in 0..generations {
+        result.final_fitness = simulation.train().ga.max_fitness();
+    }
+    if let Some(champion) = simulation.champion() {
+        result.best_fitness = champion.fitness;
+    }
+    Ok(result)
+}
+
+fn sweep(options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let base = Config::from_args(&options.config)?;
+    let keys = options.keys();
+    let settings = options.settings();
+
+    let configs = settings
+        .iter()
+        .map(|values| {
+            let mut config = base.clone();
+            for (key, value) in keys.iter().zip(values) {
+                config.set(key, value)?;
+            }
+            config.validate()?;
+            // The runs themselves are spread over `jobs` threads.
+            config.sim_threads = 1;
+            Ok(config)
+        })
+        .collect::<Result<Vec<_>, ConfigError>>()?;
+
+    fs::create_dir_all(&options.out_dir)?;
+    let mut runs_csv = BufWriter::new(File::create(options.out_dir.join("runs.csv"))?);
+    write_row(
+        &mut runs_csv,
+        ["configuration"]
+            .into_iter()
+            .chain(keys.iter().copied())
+            .chain(["seed", "final", "best"]),
+    )?;
+
+    println!(
+        "{} configurations, {} runs on {} threads",
+        configs.len(),
+        configs.len() * options.seeds,
+        options.jobs
+    );
+
+    let jobs: Vec<(usize, u64)> = (0..configs.len())
+        .flat_map(|config| (0..options.seeds as u64).map(move |seed| (config, seed)))
+        .collect();
+    let mut results = vec![vec![]; configs.len()];
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| -> Result<(), Box<dyn std::error::Error>> {
+        for _ in 0..options.jobs.min(jobs.len()) {
+            let sender = sender.clone();
+            let (jobs, configs, next) = (&jobs, &configs, &next);
+            scope.spawn(move || {
+                while let Some(&(config, seed)) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let seed = options.seed + seed;
+                    let result = run(&configs[config], seed, options.generations);
+                    if sender.send((config, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        for (done, (config, result)) in receiver.iter().enumerate() {
+            let result = result?;
+            println!(
+                "[{}/{}] configuration {config} seed {}: final {:.2}, best {:.2}",
+                done + 1,
+                jobs.len(),
+                result.seed,
+                result.final_fitness,
+                result.best_fitness
+            );
+
+            let (seed, final_fitness, best_fitness) = (
+                result.seed.to_string(),
+                result.final_fitness.to_string(),
+                result.best_fitness.to_string(),
+            );
+            write_row(
+                &mut runs_csv,
+                [config.to_string().as_str()]
+                    .into_iter()
+                    .chain(settings[config].iter().map(String::as_str))
+                    .chain([seed.as_str(), &final_fitness, &best_fitness]),
+            )?;
+            runs_csv.flush()?;
+            results[config].push(result);
+        }
+        Ok(())
+    })?;
+
+    for results in &mut results {
+        results.sort_by_key(|result| result.seed);
+    }
+    write_summary(options, &keys, &settings, &results)
+}
+
+fn write_summary(
+    options: &Options,
+    keys: &[&str],
+    settings: &[Vec<String>],
+    results: &[Vec<RunResult>],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut csv = BufWriter::new(File::create(options.out_dir.join("summary.csv"))?);
+    write_row(
+        &mut csv,
+        ["configuration"]
+            .into_iter()
+            .chain(keys.iter().copied())
+            .chain(["runs", "final_mean", "final_ci95", "best_mean", "best_ci95"]),
+    )?;
+
+    let mut summary = vec![];
+    let mut best: Option<(usize, f32, f32)> = None;
+    for (config, (values, results)) in settings.iter().zip(results).enumerate() {
+        let finals: Vec<_> = results.iter().map(|r| r.final_fitness).collect();
+        let bests: Vec<_> = results.iter().map(|r| r.best_fitness).collect();
+        let (final_mean, final_ci) = confidence_interval(&finals);
+        let (best_mean, best_ci) = confidence_interval(&bests);
+
+        let numbers = [
+            results.len().to_string(),
+            final_mean.to_string(),
+            final_ci.to_string(),
+            best_mean.to_string(),
+            best_ci.to_string(),
+        ];
+        write_row(
+            &mut csv,
+            [config.to_string().as_str()]
+                .into_iter()
+                .chain(values.iter().map(String::as_str))
+                .chain(numbers.iter().map(String::as_str)),
+        )?;
+
+        if best.is_none_or(|(_, mean, _)| best_mean > mean) {
+            best = Some((config, best_mean, best_ci));
+        }
+
+        let parameters: serde_json::Map<_, _> = keys
+            .iter()
+            .zip(values)
+            .map(|(key, value)| (key.to_string(), json!(value)))
+            .collect();
+        summary.push(json!({
+            "configuration": config,
+            "parameters": parameters,
+            "seeds": results.iter().map(|r| r.seed).collect::<Vec<_>>(),
+            "final": { "mean": final_mean, "ci95": final_ci, "samples": finals },
+            "best": { "mean": best_mean, "ci95": best_ci, "samples": bests },
+        }));
+    }
+    csv.flush()?;
+
+    let json = File::create(options.out_dir.join("summary.json"))?;
+    serde_json::to_writer_pretty(json, &summary)?;
+
+    if let Some((config, mean, ci)) = best {
+        println!(
+            "best configuration {config} {}: best fitness {mean:.2} ± {ci:.2}",
+            summary[config]["parameters"]
+        );
+    }
+    Ok(())
+}
+
+/// The mean and the half-width of its 95% confidence interval, by Student's
+/// t-distribution. The interval is unbounded for fewer than two samples.
+fn confidence_interval(samples: &[f32]) -> (f32, f32) {
+    // Two-sided 97.5% quantiles for 1..=30 degrees of freedom.
+    const T: [f32; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+
+    let n = samples.len();
+    let mean = samples.iter().sum::<f32>() / n as f32;
+    if n < 2 {
+        return (mean, f32::INFINITY);
+    }
+
+    let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f32>() / (n - 1) as f32;
+    let t = T.get(n - 2).copied().unwrap_or(1.96);
+    (mean, t * (variance / n as f32).sqrt())
+}
+
+/// Writes a CSV row, quoting fields such as inline tables that need it.
+fn write_row<'a>(
+    csv: &mut impl Write,
+    fields: impl IntoIterator<Item = &'a str>,
+) -> io::Result<()> {
+    let fields: Vec<_> = fields
+        .into_iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    writeln!(csv, "{}", fields.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn domains() {
+        assert_eq!(
+            Domain::parse("[5, 9]"),
+            Ok(Domain::Choice(vec!["5".into(), "9".into()]))
+        );
+        assert_eq!(
+            Domain::parse("[\"cma_es\", \"one_plus_lambda\"]"),
+            Ok(Domain::Choice(vec![
+                "cma_es".into(),
+                "one_plus_lambda".into()
+            ]))
+        );
+        assert_eq!(Domain::parse("3..7"), Ok(Domain::Integers(3, 7)));
+        assert_eq!(Domain::parse("0.1..0.5"), Ok(Domain::Floats(0.1, 0.5)));
+        assert!(Domain::parse("7..3").is_err());
+        assert!(Domain::parse("0.5..0.5").is_err());
+        assert!(Domain::parse("[]").is_err());
+        assert!(Domain::parse("5").is_err());
+    }
+
+    #[test]
+    fn settings() {
+        let options = parse(&[
+            "--grid",
+            "brain_neurons=[5, 9]",
+            "--grid",
+            "eye_cells=[3, 6, 9]",
+            "--random",
+            "ga_mut_coeff=0.1..0.5",
+            "--samples",
+            "2",
+        ])
+        .unwrap();
+        assert_eq!(
+            options.keys(),
+            ["brain_neurons", "eye_cells", "ga_mut_coeff"]
+        );
+
+        let settings = options.settings();
+        assert_eq!(settings.len(), 12);
+        assert_eq!(settings[0][..2], ["5", "3"]);
+        assert_eq!(settings[1][..2], ["5", "3"]);
+        assert_eq!(settings[11][..2], ["9", "9"]);
+        assert_ne!(settings[0][2], settings[1][2]);
+        assert!(settings.iter().all(|values| {
+            let coeff: f32 = values[2].parse().unwrap();
+            (0.1..0.5).contains(&coeff)
+        }));
+        assert_eq!(settings, options.settings());
+
+        assert!(parse(&["--grid", "eye_cells=3..9"]).is_err());
+        assert!(parse(&["--seeds", "0"]).is_err());
+        assert!(parse(&["--generations", "0"]).is_err());
+    }
+
+    #[test]
+    fn confidence_intervals() {
+        let (mean, ci) = confidence_interval(&[1.0, 2.0, 3.0]);
+        assert_eq!(mean, 2.0);
+        assert_relative_eq!(ci, 4.303 * (1.0f32 / 3.0).sqrt());
+
+        assert_eq!(confidence_interval(&[4.0]), (4.0, f32::INFINITY));
+    }
+}
@@ -16,23 +16,44 @@ fn draw_triangle_rotated(x: f32, y: f32, size: f32, angle: f32, color: Color) {
 
 #[macroquad::main("Simulation")]
 async fn main() {
-    let config = Config::default();
-    let mut simulation = Simulation::random(&config);
+    // A config file and key=value overrides, e.g. `config.toml world_foods=40`.
+    let simulation = Config::from_args(std::env::args().skip(1))
+        .and_then(|config| Simulation::try_random(&config, &mut OsRng));
+    let mut simulation = simulation.unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(1);
+    });
     let food_color = Color::from_rgba(0, 255, 128, 255);
-    let animal_color = Color::from_rgba(255, 255, 255, 255);
     let mut active = true;
-    let rng = &mut OsRng;
+    // Clicking prey marks them as parents of the next generation, bred with
+    // N instead of by fitness once the generation runs out.
+    let mut interactive = false;
+    let mut preferences = Preferences::new();
 
     loop {
         clear_background(GRAY);
         if is_key_pressed(KeyCode::T) {
-            for i in 0..25 {
-                if i > 0 {
-                    println!();
-                }
+            let mut controller = RunController::new()
+                .stop_when(StopCondition::MaxGenerations(25))
+                .on_generation(|i, stats: &Statistics| {
+                    if i > 0 {
+                        println!();
+                    }
+                    println!("{}", stats);
+                });
+            simulation.run(&mut controller);
+        }
 
-                let stats = simulation.train(rng);
-                println!("{}", stats);
+        if is_key_pressed(KeyCode::S) {
+            if let Err(err) = simulation.save_checkpoint("checkpoint.json") {
+                println!("{}", err);
+            }
+        }
+
+        if is_key_pressed(KeyCode::L) {
+            match Simulation::load_checkpoint("checkpoint.json") {
+                Ok(loaded) => simulation = loaded,
+                Err(err) => println!("{}", err),
             }
         }
 
@@ -40,14 +61,49 @@ async fn main() {
             active = !active;
         }
 
-        if active {
-            let stats = simulation.step(rng);
+        if is_key_pressed(KeyCode::I) {
+            interactive = !interactive;
+            preferences.clear();
+        }
+
+        if interactive && is_mouse_button_pressed(MouseButton::Left) {
+            let (x, y) = mouse_position();
+            let clicked = simulation
+                .world()
+                .animals()
+                .iter()
+                .take(simulation.config().world_animals)
+                .enumerate()
+                .map(|(i, animal)| {
+                    let dx = animal.position().x * screen_width() - x;
+                    let dy = animal.position().y * screen_width() - y;
+                    (i, dx * dx + dy * dy)
+                })
+                .filter(|&(_, distance)| {
+                    distance.sqrt() < simulation.config().food_size * 2.0 * screen_width()
+                })
+                .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+            if let Some((i, _)) = clicked {
+                preferences.toggle_favourite(i);
+            }
+        }
+
+        if interactive && is_key_pressed(KeyCode::N) && !preferences.is_empty() {
+            println!("{}", simulation.evolve_interactively(&preferences));
+            preferences.clear();
+        }
+
+        let generation_over = simulation.age() >= simulation.config().sim_generation_length;
+        if active && !(interactive && generation_over) {
+            let stats = simulation.step();
 
             if let Some(stats) = stats {
                 println!("{}", stats);
             }
         }
 
+        let config = simulation.config();
         for food in simulation.world().foods() {
             let x = food.position().x * screen_width();
             let y = food.position().y * screen_width();
@@ -55,21 +111,27 @@ async fn main() {
             draw_circle(x, y, config.food_size / 2.0 * screen_width(), food_color);
         }
 
-        for animal in simulation.world().animals() {
-            let mut r = 64u8;
-            let mut g = 0u8;
-            let mut b = 64u8;
+        for (i, animal) in simulation.world().animals().iter().enumerate() {
+            if interactive && preferences.is_favourite(i) {
+                draw_circle_lines(
+                    animal.position().x * screen_width(),
+                    animal.position().y * screen_width(),
+                    config.food_size * 2.0 * screen_width(),
+                    1.0,
+                    WHITE,
+                );
+            }
 
-            if animal.stunned > 0 {
-                r = 0;
-                g = 0;
-                b = 0;
+            let (r, g, b) = if animal.stunned > 0 {
+                (0u8, 0u8, 0u8)
             } else {
-                if animal.boosting {
-                    r = 128;
-                }
-                g = (animal.satiation * 8).min(255usize) as u8;
-            }
+                let r = match (animal.species(), animal.boosting) {
+                    (Species::Predator, _) => 255u8,
+                    (Species::Prey, true) => 128u8,
+                    (Species::Prey, false) => 64u8,
+                };
+                (r, (animal.satiation * 8).min(255usize) as u8, 64u8)
+            };
 
             draw_triangle_rotated(
                 animal.position().x * screen_width(),
@@ -81,12 +143,10 @@ async fn main() {
 
             let angle_per_cell = config.eye_fov_angle / (config.eye_cells as f32);
 
-            for cell_id in (0..config.eye_cells) {
+            for cell_id in 0..config.eye_cells {
                 let angle_from = animal.rotation().angle() - config.eye_fov_angle / 2.0
                     + (cell_id as f32) * angle_per_cell
                     + PI / 2.0;
-                let angle_to = angle_from + angle_per_cell;
-
                 let energy = animal.vision()[cell_id].round();
 
                 draw_arc(